@@ -43,15 +43,32 @@ mod accounts_signing {
     use crate::{
         api::Web3,
         error,
+        fees::FeeEstimator,
+        recovery,
         signing::Signature,
-        types::{AccessList, Address, Bytes, SignedData, SignedTransaction, TransactionParameters, U256, U64},
+        types::{
+            AccessList, AccessListItem, Address, Authorization, AuthorizationList, BlobSidecar, Bytes, CallRequest,
+            DecodedTransaction, ReplacedTransaction, Replacement, SignedAuthorization, SignedData, SignedTransaction,
+            TransactionId, TransactionParameters, UnsignedTransaction, U256, U64,
+        },
     };
-    use rlp::RlpStream;
-    use std::convert::TryInto;
+    use ethereum_types::BigEndianHash;
+    use rlp::{Rlp, RlpStream};
 
     const LEGACY_TX_ID: u64 = 0;
     const ACCESSLISTS_TX_ID: u64 = 1;
     const EIP1559_TX_ID: u64 = 2;
+    const BLOB_TX_ID: u64 = 3;
+    const SET_CODE_TX_ID: u64 = 4;
+
+    /// Minimum fee increase, in percent, accepted by nodes for a replacement transaction.
+    const REPLACEMENT_FEE_BUMP: u64 = 10;
+
+    /// Raises `fee` by at least `REPLACEMENT_FEE_BUMP` percent, rounding up.
+    fn bump_fee(fee: U256) -> U256 {
+        let bumped = (fee * (100 + REPLACEMENT_FEE_BUMP) + 99) / 100;
+        bumped.max(fee + 1)
+    }
 
     impl<T: Transport> Accounts<T> {
         /// Gets the parent `web3` namespace
@@ -62,14 +79,55 @@ mod accounts_signing {
         /// Signs an Ethereum transaction with a given private key.
         ///
         /// Transaction signing can perform RPC requests in order to fill missing
-        /// parameters required for signing `nonce`, `gas_price` and `chain_id`. Note
+        /// parameters required for signing `nonce`, `gas`, `gas_price` and `chain_id`. Note
         /// that if all transaction parameters were provided, this future will resolve
         /// immediately.
+        ///
+        /// Missing fees of EIP-1559, blob and set-code transactions are estimated with a `FeeEstimator`
+        /// rather than derived from the gas price. The max fee per blob gas defaults to twice
+        /// the current blob base fee.
+        ///
+        /// The gas of blob and set-code transactions isn't estimated, as a `CallRequest` can't
+        /// carry their blob hashes or authorizations, and must be set.
         pub async fn sign_transaction<K: signing::Key>(
             &self,
             tx: TransactionParameters,
             key: K,
         ) -> error::Result<SignedTransaction> {
+            self.fill_transaction(tx, key.address()).await?.sign(key)
+        }
+
+        /// Signs a transaction with an asynchronous, possibly remote, signer.
+        ///
+        /// Missing parameters are filled as with `sign_transaction` before the transaction is
+        /// handed to the signer.
+        pub async fn sign_transaction_with<S: crate::signer::Signer>(
+            &self,
+            tx: TransactionParameters,
+            signer: &S,
+        ) -> error::Result<SignedTransaction> {
+            let unsigned = self.prepare_transaction(tx, signer.address()).await?;
+            signer.sign_transaction(unsigned).await
+        }
+
+        /// Prepares a transaction from `from` to be signed externally.
+        ///
+        /// Missing parameters are filled as with `sign_transaction`. Sign the returned
+        /// transaction's signing hash, then assemble it with `UnsignedTransaction::attach_signature`.
+        pub async fn prepare_transaction(
+            &self,
+            tx: TransactionParameters,
+            from: Address,
+        ) -> error::Result<UnsignedTransaction> {
+            self.fill_transaction(tx, from).await?.unsigned(from)
+        }
+
+        /// Fills the missing parameters of a transaction sent by `from`.
+        async fn fill_transaction(
+            &self,
+            tx: TransactionParameters,
+            from: Address,
+        ) -> error::Result<TransactionParameters> {
             macro_rules! maybe {
                 ($o: expr, $f: expr) => {
                     async {
@@ -80,44 +138,180 @@ mod accounts_signing {
                     }
                 };
             }
-            let from = key.address();
 
-            let gas_price = match tx.transaction_type {
-                Some(tx_type) if tx_type == U64::from(EIP1559_TX_ID) && tx.max_fee_per_gas.is_some() => {
-                    tx.max_fee_per_gas
+            let tx_type = tx.transaction_type.map(|t| t.as_u64());
+            if tx.gas.is_none() && matches!(tx_type, Some(BLOB_TX_ID | SET_CODE_TX_ID)) {
+                return Err(error::Error::MissingParameter("gas"));
+            }
+            let eip1559 = matches!(tx_type, Some(EIP1559_TX_ID) | Some(BLOB_TX_ID) | Some(SET_CODE_TX_ID));
+            let gas_price = if eip1559 {
+                tx.max_fee_per_gas.or(tx.gas_price)
+            } else {
+                tx.gas_price
+            };
+            let fees = async {
+                match (gas_price, tx.max_priority_fee_per_gas) {
+                    (Some(gas_price), Some(max_priority_fee_per_gas)) if eip1559 => {
+                        Ok((gas_price, max_priority_fee_per_gas))
+                    }
+                    (gas_price, max_priority_fee_per_gas) if eip1559 => {
+                        let fees = FeeEstimator::new(self.web3().eth()).estimate().await?;
+                        let max_priority_fee_per_gas =
+                            max_priority_fee_per_gas.unwrap_or(fees.max_priority_fee_per_gas);
+                        let gas_price = gas_price.unwrap_or(fees.max_fee_per_gas.max(max_priority_fee_per_gas));
+                        Ok((gas_price, max_priority_fee_per_gas.min(gas_price)))
+                    }
+                    (gas_price, _) => {
+                        let gas_price = maybe!(gas_price, self.web3().eth().gas_price()).await?;
+                        Ok((gas_price, gas_price))
+                    }
                 }
-                _ => tx.gas_price,
             };
 
-            let (nonce, gas_price, chain_id) = futures::future::try_join3(
-                maybe!(tx.nonce, self.web3().eth().transaction_count(from, None)),
-                maybe!(gas_price, self.web3().eth().gas_price()),
-                maybe!(tx.chain_id.map(U256::from), self.web3().eth().chain_id()),
-            )
-            .await?;
-            let chain_id = chain_id.as_u64();
-
-            let max_priority_fee_per_gas = match tx.transaction_type {
-                Some(tx_type) if tx_type == U64::from(EIP1559_TX_ID) => {
-                    tx.max_priority_fee_per_gas.unwrap_or(gas_price)
+            let gas = maybe!(tx.gas, async {
+                let call = CallRequest {
+                    from: Some(from),
+                    ..tx.clone().into()
+                };
+                let estimate = self.web3().eth().estimate_gas(call, None).await?;
+                Ok(estimate * tx.gas_multiplier_percent / 100)
+            });
+
+            let blob_fee = async {
+                match tx.max_fee_per_blob_gas {
+                    Some(max_fee_per_blob_gas) => Ok(max_fee_per_blob_gas),
+                    None if tx_type == Some(BLOB_TX_ID) => Ok(self.web3().eth().blob_base_fee().await? * 2),
+                    None => Ok(U256::zero()),
                 }
-                _ => gas_price,
             };
 
-            let tx = Transaction {
-                to: tx.to,
-                nonce,
-                gas: tx.gas,
-                gas_price,
-                value: tx.value,
-                data: tx.data.0,
-                transaction_type: tx.transaction_type,
-                access_list: tx.access_list.unwrap_or_default(),
-                max_priority_fee_per_gas,
+            let (nonce, (gas_price, max_priority_fee_per_gas), chain_id, gas, max_fee_per_blob_gas) =
+                futures::future::try_join5(
+                    maybe!(tx.nonce, self.web3().eth().transaction_count(from, None)),
+                    fees,
+                    maybe!(tx.chain_id.map(U256::from), self.web3().eth().chain_id()),
+                    gas,
+                    blob_fee,
+                )
+                .await?;
+
+            Ok(TransactionParameters {
+                nonce: Some(nonce),
+                gas: Some(gas),
+                gas_price: Some(gas_price),
+                chain_id: Some(chain_id.as_u64()),
+                max_fee_per_gas: Some(gas_price),
+                max_priority_fee_per_gas: Some(max_priority_fee_per_gas),
+                max_fee_per_blob_gas: Some(max_fee_per_blob_gas),
+                ..tx
+            })
+        }
+
+        /// Re-signs a pending transaction with the same nonce and bumped fees.
+        ///
+        /// The transaction to replace is either decoded from its raw bytes or looked up by hash,
+        /// in which case it must still be known to the node. Its fees are raised by at least 10%,
+        /// the default replacement minimum of most clients, or to the current gas price if that
+        /// is higher. Both the max fee and the priority fee are bumped for EIP-1559 transactions,
+        /// and the blob fee for blob transactions. Speeding up a blob transaction requires its
+        /// sidecar, given with `ReplacedTransaction::Blob` or as part of a signed transaction in
+        /// network form, otherwise `Error::MissingParameter` is returned. The blob versioned
+        /// hashes and the authorizations of set-code transactions are kept.
+        ///
+        /// With `Replacement::Cancel`, a zero-value transfer to the sender itself is signed
+        /// instead, as a plain EIP-1559 transaction if the original was a blob or set-code one.
+        ///
+        /// The replacement is only signed; send it with `Eth::send_raw_transaction`.
+        pub async fn sign_replacement<K: signing::Key>(
+            &self,
+            tx: impl Into<ReplacedTransaction>,
+            replacement: Replacement,
+            key: K,
+        ) -> error::Result<SignedTransaction> {
+            let eth = self.web3().eth();
+            let lookup = |hash| futures::future::try_join(eth.transaction(TransactionId::Hash(hash)), eth.gas_price());
+            let (original, chain_id, blob_sidecar, gas_price) = match tx.into() {
+                ReplacedTransaction::Hash(hash) => {
+                    let (original, gas_price) = lookup(hash).await?;
+                    (original, None, None, gas_price)
+                }
+                ReplacedTransaction::Blob(hash, sidecar) => {
+                    let (original, gas_price) = lookup(hash).await?;
+                    (original, None, Some(sidecar), gas_price)
+                }
+                ReplacedTransaction::Signed(signed) => {
+                    let decoded = DecodedTransaction::decode(&signed.raw_transaction.0)?;
+                    (
+                        Some(decoded.tx),
+                        decoded.chain_id,
+                        decoded.blob_sidecar,
+                        eth.gas_price().await?,
+                    )
+                }
+            };
+            let original =
+                original.ok_or_else(|| error::Error::InvalidResponse("transaction to replace not found".into()))?;
+            if original.block_hash.is_some() {
+                return Err(error::Error::InvalidResponse(
+                    "transaction to replace is already mined".into(),
+                ));
+            }
+            let from = key.address();
+            if original.from.is_some_and(|sender| sender != from) {
+                return Err(error::Error::InvalidResponse(
+                    "transaction to replace was not sent by the signing key".into(),
+                ));
+            }
+
+            let mut params = TransactionParameters {
+                nonce: Some(original.nonce),
+                to: original.to,
+                gas: Some(original.gas),
+                value: original.value,
+                data: original.input,
+                chain_id,
+                transaction_type: original.transaction_type,
+                access_list: original.access_list,
+                max_fee_per_blob_gas: original.max_fee_per_blob_gas.map(bump_fee),
+                blob_versioned_hashes: original.blob_versioned_hashes,
+                blob_sidecar,
+                authorization_list: original.authorization_list,
+                ..Default::default()
             };
+            match (original.max_fee_per_gas, original.max_priority_fee_per_gas) {
+                (Some(max_fee_per_gas), Some(max_priority_fee_per_gas)) => {
+                    let max_priority_fee_per_gas = bump_fee(max_priority_fee_per_gas);
+                    params.max_fee_per_gas =
+                        Some(bump_fee(max_fee_per_gas).max(gas_price).max(max_priority_fee_per_gas));
+                    params.max_priority_fee_per_gas = Some(max_priority_fee_per_gas);
+                }
+                _ => {
+                    params.gas_price = Some(bump_fee(original.gas_price.unwrap_or_default()).max(gas_price));
+                }
+            }
+            let tx_type = params.transaction_type.map(|t| t.as_u64());
+            match replacement {
+                Replacement::SpeedUp if tx_type == Some(BLOB_TX_ID) && params.blob_sidecar.is_none() => {
+                    return Err(error::Error::MissingParameter("blob_sidecar"));
+                }
+                Replacement::SpeedUp => {}
+                Replacement::Cancel => {
+                    params.to = Some(from);
+                    params.gas = Some(21_000.into());
+                    params.value = U256::zero();
+                    params.data = Bytes::default();
+                    params.access_list = None;
+                    if matches!(tx_type, Some(BLOB_TX_ID | SET_CODE_TX_ID)) {
+                        params.transaction_type = Some(EIP1559_TX_ID.into());
+                    }
+                    params.max_fee_per_blob_gas = None;
+                    params.blob_versioned_hashes = None;
+                    params.blob_sidecar = None;
+                    params.authorization_list = None;
+                }
+            }
 
-            let signed = tx.sign(key, chain_id);
-            Ok(signed)
+            self.sign_transaction(params, key).await
         }
 
         /// Sign arbitrary string data.
@@ -127,46 +321,57 @@ mod accounts_signing {
         /// notation, that is the recovery value `v` is either `27` or `28` (as
         /// opposed to the standard notation where `v` is either `0` or `1`). This
         /// is important to consider when using this signature with other crates.
+        ///
+        /// Panics if the key fails to sign, see `try_sign`.
         pub fn sign<S>(&self, message: S, key: impl signing::Key) -> SignedData
         where
             S: AsRef<[u8]>,
         {
-            let message = message.as_ref();
-            let message_hash = self.hash_message(message);
-
-            let signature = key
-                .sign(&message_hash.0, None)
-                .expect("hash is non-zero 32-bytes; qed");
-            let v = signature
-                .v
-                .try_into()
-                .expect("signature recovery in electrum notation always fits in a u8");
-
-            let signature_bytes = Bytes({
-                let mut bytes = Vec::with_capacity(65);
-                bytes.extend_from_slice(signature.r.as_bytes());
-                bytes.extend_from_slice(signature.s.as_bytes());
-                bytes.push(v);
-                bytes
-            });
+            self.try_sign(message, key).expect("hash is non-zero 32-bytes; qed")
+        }
 
-            // We perform this allocation only after all previous fallible actions have completed successfully.
-            let message = message.to_owned();
+        /// Like `sign`, but returns the error of a key failing to sign.
+        pub fn try_sign<S>(&self, message: S, key: impl signing::Key) -> error::Result<SignedData>
+        where
+            S: AsRef<[u8]>,
+        {
+            Ok(signing::sign_data(message.as_ref(), &key)?)
+        }
 
-            SignedData {
-                message,
-                message_hash,
-                v,
-                r: signature.r,
-                s: signature.s,
-                signature: signature_bytes,
-            }
+        /// Signs an EIP-7702 authorization to delegate the key's account to contract code.
+        ///
+        /// The authorizing account's nonce must be the one it will have when the set-code
+        /// transaction is executed, i.e. incremented if the account also sends the transaction.
+        /// The authority can be recovered with `recover`.
+        ///
+        /// Panics if the key fails to sign, see `try_sign_authorization`.
+        pub fn sign_authorization(&self, authorization: Authorization, key: impl signing::Key) -> SignedAuthorization {
+            self.try_sign_authorization(authorization, key)
+                .expect("hash is non-zero 32-bytes; qed")
+        }
+
+        /// Like `sign_authorization`, but returns the error of a key failing to sign.
+        pub fn try_sign_authorization(
+            &self,
+            authorization: Authorization,
+            key: impl signing::Key,
+        ) -> error::Result<SignedAuthorization> {
+            let signature = key.sign_message(authorization.signature_hash().as_fixed_bytes())?;
+
+            Ok(SignedAuthorization {
+                chain_id: authorization.chain_id,
+                address: authorization.address,
+                nonce: authorization.nonce,
+                y_parity: signature.v.into(),
+                r: U256::from_big_endian(signature.r.as_bytes()),
+                s: U256::from_big_endian(signature.s.as_bytes()),
+            })
         }
 
         /// Recovers the Ethereum address which was used to sign the given data.
         ///
-        /// Recovery signature data uses 'Electrum' notation, this means the `v`
-        /// value is expected to be either `27` or `28`.
+        /// The `v` value can be the y-parity, in 'Electrum' notation (`27` or `28`), or with
+        /// EIP-155 replay protection.
         pub fn recover<R>(&self, recovery: R) -> error::Result<Address>
         where
             R: Into<Recovery>,
@@ -179,21 +384,141 @@ mod accounts_signing {
             let (signature, recovery_id) = recovery
                 .as_signature()
                 .ok_or(error::Error::Recovery(signing::RecoveryError::InvalidSignature))?;
+            Ok(recovery::recover_address(&message_hash, &signature, recovery_id as u8)?)
+        }
+
+        /// Decodes a raw signed transaction and recovers its sender.
+        ///
+        /// Legacy, EIP-2930, EIP-1559, blob and set-code transactions are supported, typed ones
+        /// being prefixed with their type byte as in `SignedTransaction::raw_transaction`. Blob
+        /// transactions may be in their network form, with the sidecar. The decoded transaction
+        /// has its `hash`, `from` and `raw` fields set, and no block information.
+        pub fn decode_transaction(&self, raw: &[u8]) -> error::Result<DecodedTransaction> {
+            DecodedTransaction::decode(raw)
+        }
+    }
+
+    impl DecodedTransaction {
+        /// Decodes a raw signed transaction and recovers its sender, as `Accounts::decode_transaction`.
+        ///
+        /// Signatures with a high `s` are rejected, as required by EIP-2.
+        pub fn decode(raw: &[u8]) -> error::Result<Self> {
+            let (tx, chain_id, signature) = Transaction::decode(raw)?;
+            let tx_type = tx.transaction_type.map_or(LEGACY_TX_ID, |t| t.as_u64());
+
+            let (message, recovery_id) = match chain_id {
+                Some(chain_id) if tx_type != LEGACY_TX_ID => (tx.encode(chain_id, None)?, Some(signature.v)),
+                Some(chain_id) => (tx.encode(chain_id, None)?, signature.v.checked_sub(chain_id * 2 + 35)),
+                None => {
+                    // signed before EIP-155, without the chain ID in the signed payload
+                    let mut stream = RlpStream::new_list(6);
+                    tx.rlp_append_legacy(&mut stream);
+                    (stream.out().to_vec(), signature.v.checked_sub(27))
+                }
+            };
+            let recovery_id = recovery_id
+                .filter(|id| *id <= 1 && recovery::is_low_s(&signature.s))
+                .ok_or(error::Error::Recovery(signing::RecoveryError::InvalidSignature))?;
+            let mut rs = [0; 64];
+            rs[..32].copy_from_slice(signature.r.as_bytes());
+            rs[32..].copy_from_slice(signature.s.as_bytes());
+            let from = recovery::recover_address(&signing::keccak256(&message).into(), &rs, recovery_id as u8)?;
+
+            // the hash of blob transactions doesn't cover the sidecar
+            let hash = signing::keccak256(&tx.encode(chain_id.unwrap_or_default(), Some(&signature))?);
+            let eip1559 = matches!(tx_type, EIP1559_TX_ID | BLOB_TX_ID | SET_CODE_TX_ID);
+            let blob = tx_type == BLOB_TX_ID;
+            let decoded = crate::types::Transaction {
+                hash: hash.into(),
+                nonce: tx.nonce,
+                from: Some(from),
+                to: tx.to,
+                value: tx.value,
+                gas_price: (!eip1559).then_some(tx.gas_price),
+                gas: tx.gas,
+                input: tx.data.into(),
+                v: Some(signature.v.into()),
+                r: Some(U256::from_big_endian(signature.r.as_bytes())),
+                s: Some(U256::from_big_endian(signature.s.as_bytes())),
+                raw: Some(raw.to_vec().into()),
+                transaction_type: Some(tx_type.into()),
+                access_list: (tx_type != LEGACY_TX_ID).then_some(tx.access_list),
+                max_fee_per_gas: eip1559.then_some(tx.gas_price),
+                max_priority_fee_per_gas: eip1559.then_some(tx.max_priority_fee_per_gas),
+                max_fee_per_blob_gas: blob.then_some(tx.max_fee_per_blob_gas),
+                blob_versioned_hashes: blob.then_some(tx.blob_versioned_hashes),
+                authorization_list: (tx_type == SET_CODE_TX_ID).then_some(tx.authorization_list),
+                ..Default::default()
+            };
+            Ok(DecodedTransaction {
+                chain_id,
+                tx: decoded,
+                blob_sidecar: tx.blob_sidecar,
+            })
+        }
+    }
 
-            let mut recoverable_signature: [u8; 65] = [0; 65];
-            recoverable_signature[..64].copy_from_slice(&signature[..]);
-            recoverable_signature[64] = recovery_id as u8;
+    impl TransactionParameters {
+        /// Signs the transaction offline, without any RPC.
+        ///
+        /// Unlike `Accounts::sign_transaction`, nothing is fetched or estimated: the nonce, gas,
+        /// chain ID and the fees of the transaction type must be set, otherwise
+        /// `Error::MissingParameter` is returned. As when signing online, the gas price is used
+        /// as the max fee of EIP-1559 transactions without `max_fee_per_gas`.
+        pub fn sign(self, key: impl signing::Key) -> error::Result<SignedTransaction> {
+            let (tx, chain_id) = Transaction::from_parameters(self)?;
+            tx.sign(key, chain_id)
+        }
+
+        /// Prepares the transaction to be signed by `from` outside of the crate.
+        ///
+        /// The parameters must be fully specified, as for `sign`.
+        pub fn unsigned(self, from: Address) -> error::Result<UnsignedTransaction> {
+            let (tx, chain_id) = Transaction::from_parameters(self.clone())?;
+            let payload = tx.encode(chain_id, None)?;
+            Ok(UnsignedTransaction {
+                parameters: self,
+                from,
+                signing_hash: signing::keccak256(&payload).into(),
+                payload: payload.into(),
+            })
+        }
+    }
+
+    impl UnsignedTransaction {
+        /// Assembles the transaction with a signature of its signing hash.
+        ///
+        /// `v` can be the y-parity, in 'Electrum' notation, or with EIP-155 replay protection for
+        /// the transaction's chain. Fails with `RecoveryError::InvalidSignature` if the signature
+        /// wasn't produced by the expected sender, or has a high `s`.
+        pub fn attach_signature(self, signature: Signature) -> error::Result<SignedTransaction> {
+            let (tx, chain_id) = Transaction::from_parameters(self.parameters)?;
+            let invalid = error::Error::Recovery(signing::RecoveryError::InvalidSignature);
+            let y_parity = match signature.v {
+                0 | 1 => signature.v,
+                27 | 28 => signature.v - 27,
+                v if v >= 35 && (v - 35) / 2 == chain_id => (v - 35) % 2,
+                _ => return Err(invalid),
+            };
 
-            let mut pub_key = [0; 33];
-            ink_env::ecdsa_recover(&recoverable_signature, message_hash.as_fixed_bytes(), &mut pub_key)
-                .or(Err(error::Error::Recovery(signing::RecoveryError::InvalidSignature)))?;
+            let mut rs = [0; 64];
+            rs[..32].copy_from_slice(signature.r.as_bytes());
+            rs[32..].copy_from_slice(signature.s.as_bytes());
+            if !recovery::is_low_s(&signature.s)
+                || recovery::recover_address(&self.signing_hash, &rs, y_parity as u8)? != self.from
+            {
+                return Err(invalid);
+            }
 
-            let mut address = [0; 20];
-            ink_env::ecdsa_to_eth_address(&pub_key, &mut address)
-                .or(Err(error::Error::Recovery(signing::RecoveryError::InvalidSignature)))?;
-            Ok(address.into())
+            let legacy = matches!(tx.transaction_type.map(|t| t.as_u64()), Some(LEGACY_TX_ID) | None);
+            let signature = Signature {
+                v: if legacy { y_parity + 35 + chain_id * 2 } else { y_parity },
+                ..signature
+            };
+            tx.signed(chain_id, self.signing_hash, signature)
         }
     }
+
     /// A transaction used for RLP encoding, hashing and signing.
     #[derive(Debug)]
     pub struct Transaction {
@@ -206,9 +531,76 @@ mod accounts_signing {
         pub transaction_type: Option<U64>,
         pub access_list: AccessList,
         pub max_priority_fee_per_gas: U256,
+        pub max_fee_per_blob_gas: U256,
+        pub blob_versioned_hashes: Vec<H256>,
+        pub blob_sidecar: Option<BlobSidecar>,
+        pub authorization_list: AuthorizationList,
     }
 
     impl Transaction {
+        /// Converts fully specified parameters, returning the transaction and its chain ID.
+        fn from_parameters(tx: TransactionParameters) -> error::Result<(Self, u64)> {
+            let missing = error::Error::MissingParameter;
+            let tx_type = tx.transaction_type.map_or(LEGACY_TX_ID, |t| t.as_u64());
+            let (gas_price, max_priority_fee_per_gas) = match tx_type {
+                EIP1559_TX_ID | BLOB_TX_ID | SET_CODE_TX_ID => (
+                    tx.max_fee_per_gas.or(tx.gas_price).ok_or(missing("max_fee_per_gas"))?,
+                    tx.max_priority_fee_per_gas.ok_or(missing("max_priority_fee_per_gas"))?,
+                ),
+                _ => {
+                    let gas_price = tx.gas_price.ok_or(missing("gas_price"))?;
+                    (gas_price, gas_price)
+                }
+            };
+            let max_fee_per_blob_gas = match tx.max_fee_per_blob_gas {
+                Some(max_fee_per_blob_gas) => max_fee_per_blob_gas,
+                None if tx_type == BLOB_TX_ID => return Err(missing("max_fee_per_blob_gas")),
+                None => U256::zero(),
+            };
+            let blob_versioned_hashes = match (tx.blob_versioned_hashes, &tx.blob_sidecar) {
+                (Some(hashes), Some(sidecar)) if hashes != sidecar.versioned_hashes() => {
+                    return Err(error::Error::InvalidParameter("blob_versioned_hashes"))
+                }
+                (Some(hashes), _) => hashes,
+                (None, Some(sidecar)) => sidecar.versioned_hashes(),
+                (None, None) => Vec::new(),
+            };
+            if tx_type == BLOB_TX_ID {
+                if tx.to.is_none() {
+                    return Err(missing("to"));
+                }
+                if blob_versioned_hashes.is_empty() {
+                    return Err(missing("blob_versioned_hashes"));
+                }
+            }
+            let authorization_list = tx.authorization_list.unwrap_or_default();
+            if tx_type == SET_CODE_TX_ID {
+                if tx.to.is_none() {
+                    return Err(missing("to"));
+                }
+                if authorization_list.is_empty() {
+                    return Err(missing("authorization_list"));
+                }
+            }
+
+            let transaction = Transaction {
+                to: tx.to,
+                nonce: tx.nonce.ok_or(missing("nonce"))?,
+                gas: tx.gas.ok_or(missing("gas"))?,
+                gas_price,
+                value: tx.value,
+                data: tx.data.0,
+                transaction_type: tx.transaction_type,
+                access_list: tx.access_list.unwrap_or_default(),
+                max_priority_fee_per_gas,
+                max_fee_per_blob_gas,
+                blob_versioned_hashes,
+                blob_sidecar: tx.blob_sidecar,
+                authorization_list,
+            };
+            Ok((transaction, tx.chain_id.ok_or(missing("chain_id"))?))
+        }
+
         fn rlp_append_legacy(&self, stream: &mut RlpStream) {
             stream.append(&self.nonce);
             stream.append(&self.gas_price);
@@ -267,6 +659,56 @@ mod accounts_signing {
             // append chain_id. from EIP-2930: chainId is defined to be an integer of arbitrary size.
             stream.append(&chain_id);
 
+            self.rlp_append_eip1559(&mut stream);
+            self.rlp_append_access_list(&mut stream);
+
+            if let Some(signature) = signature {
+                self.rlp_append_signature(&mut stream, signature);
+            }
+
+            stream
+        }
+
+        fn encode_blob_payload(&self, chain_id: u64, signature: Option<&Signature>) -> RlpStream {
+            let mut stream = RlpStream::new();
+
+            let list_size = if signature.is_some() { 14 } else { 11 };
+            stream.begin_list(list_size);
+
+            stream.append(&chain_id);
+
+            self.rlp_append_eip1559(&mut stream);
+            self.rlp_append_access_list(&mut stream);
+            stream.append(&self.max_fee_per_blob_gas);
+            stream.append_list(&self.blob_versioned_hashes);
+
+            if let Some(signature) = signature {
+                self.rlp_append_signature(&mut stream, signature);
+            }
+
+            stream
+        }
+
+        fn encode_set_code_payload(&self, chain_id: u64, signature: Option<&Signature>) -> RlpStream {
+            let mut stream = RlpStream::new();
+
+            let list_size = if signature.is_some() { 13 } else { 10 };
+            stream.begin_list(list_size);
+
+            stream.append(&chain_id);
+
+            self.rlp_append_eip1559(&mut stream);
+            self.rlp_append_access_list(&mut stream);
+            self.rlp_append_authorization_list(&mut stream);
+
+            if let Some(signature) = signature {
+                self.rlp_append_signature(&mut stream, signature);
+            }
+
+            stream
+        }
+
+        fn rlp_append_eip1559(&self, stream: &mut RlpStream) {
             stream.append(&self.nonce);
             stream.append(&self.max_priority_fee_per_gas);
             stream.append(&self.gas_price);
@@ -278,14 +720,6 @@ mod accounts_signing {
             }
             stream.append(&self.value);
             stream.append(&self.data);
-
-            self.rlp_append_access_list(&mut stream);
-
-            if let Some(signature) = signature {
-                self.rlp_append_signature(&mut stream, signature);
-            }
-
-            stream
         }
 
         fn rlp_append_signature(&self, stream: &mut RlpStream, signature: &Signature) {
@@ -306,71 +740,249 @@ mod accounts_signing {
             }
         }
 
-        fn encode(&self, chain_id: u64, signature: Option<&Signature>) -> Vec<u8> {
+        fn rlp_append_authorization_list(&self, stream: &mut RlpStream) {
+            stream.begin_list(self.authorization_list.len());
+            for auth in self.authorization_list.iter() {
+                stream.begin_list(6);
+                stream.append(&auth.chain_id);
+                stream.append(&auth.address);
+                stream.append(&auth.nonce);
+                stream.append(&auth.y_parity);
+                stream.append(&auth.r);
+                stream.append(&auth.s);
+            }
+        }
+
+        fn encode(&self, chain_id: u64, signature: Option<&Signature>) -> error::Result<Vec<u8>> {
             match self.transaction_type.map(|t| t.as_u64()) {
                 Some(LEGACY_TX_ID) | None => {
                     let stream = self.encode_legacy(chain_id, signature);
-                    stream.out().to_vec()
+                    Ok(stream.out().to_vec())
                 }
 
                 Some(ACCESSLISTS_TX_ID) => {
                     let tx_id: u8 = ACCESSLISTS_TX_ID as u8;
                     let stream = self.encode_access_list_payload(chain_id, signature);
-                    [&[tx_id], stream.as_raw()].concat()
+                    Ok([&[tx_id], stream.as_raw()].concat())
                 }
 
                 Some(EIP1559_TX_ID) => {
                     let tx_id: u8 = EIP1559_TX_ID as u8;
                     let stream = self.encode_eip1559_payload(chain_id, signature);
-                    [&[tx_id], stream.as_raw()].concat()
+                    Ok([&[tx_id], stream.as_raw()].concat())
                 }
 
-                _ => {
-                    panic!("Unsupported transaction type");
+                Some(BLOB_TX_ID) => {
+                    let tx_id: u8 = BLOB_TX_ID as u8;
+                    let stream = self.encode_blob_payload(chain_id, signature);
+                    Ok([&[tx_id], stream.as_raw()].concat())
+                }
+
+                Some(SET_CODE_TX_ID) => {
+                    let tx_id: u8 = SET_CODE_TX_ID as u8;
+                    let stream = self.encode_set_code_payload(chain_id, signature);
+                    Ok([&[tx_id], stream.as_raw()].concat())
+                }
+
+                Some(tx_type) => Err(unsupported_type(tx_type)),
+            }
+        }
+
+        /// Decodes a raw signed transaction into its fields, chain ID and signature.
+        ///
+        /// The chain ID is `None` for legacy transactions signed without replay protection.
+        fn decode(raw: &[u8]) -> error::Result<(Self, Option<u64>, Signature)> {
+            let tx_type = match raw.first() {
+                None => return Err(error::Error::Decoder("empty transaction".into())),
+                Some(byte) if *byte >= 0xc0 => LEGACY_TX_ID,
+                Some(byte) => u64::from(*byte),
+            };
+            let payload = match tx_type {
+                LEGACY_TX_ID => raw,
+                _ => &raw[1..],
+            };
+            let mut rlp = Rlp::new(payload);
+            let info = rlp.payload_info()?;
+            if info.header_len + info.value_len != payload.len() {
+                return Err(error::Error::Decoder("trailing bytes after the transaction".into()));
+            }
+            // the network form of blob transactions wraps the transaction and its sidecar
+            let mut blob_sidecar = None;
+            if tx_type == BLOB_TX_ID && rlp.at(0)?.is_list() {
+                if rlp.item_count()? != 4 {
+                    return Err(rlp::DecoderError::RlpIncorrectListLen.into());
+                }
+                let list = |index| -> Result<Vec<Bytes>, rlp::DecoderError> {
+                    rlp.at(index)?
+                        .iter()
+                        .map(|item| item.data().map(|data| data.to_vec().into()))
+                        .collect()
+                };
+                blob_sidecar = Some(BlobSidecar {
+                    blobs: list(1)?,
+                    commitments: list(2)?,
+                    proofs: list(3)?,
+                });
+                rlp = rlp.at(0)?;
+            }
+            // index of the nonce, after the chain ID of typed transactions, and number of fee fields
+            let (expected_items, nonce_at, fees) = match tx_type {
+                LEGACY_TX_ID => (9, 0, 1),
+                ACCESSLISTS_TX_ID => (11, 1, 1),
+                EIP1559_TX_ID => (12, 1, 2),
+                BLOB_TX_ID => (14, 1, 2),
+                SET_CODE_TX_ID => (13, 1, 2),
+                tx_type => return Err(unsupported_type(tx_type)),
+            };
+            if rlp.item_count()? != expected_items {
+                return Err(rlp::DecoderError::RlpIncorrectListLen.into());
+            }
+
+            let gas_at = nonce_at + fees + 1;
+            let to = rlp.at(gas_at + 1)?;
+            let mut tx = Transaction {
+                nonce: rlp.val_at(nonce_at)?,
+                gas_price: rlp.val_at(nonce_at + fees)?,
+                gas: rlp.val_at(gas_at)?,
+                to: if to.is_empty() { None } else { Some(to.as_val()?) },
+                value: rlp.val_at(gas_at + 2)?,
+                data: rlp.val_at(gas_at + 3)?,
+                transaction_type: (tx_type != LEGACY_TX_ID).then(|| tx_type.into()),
+                access_list: AccessList::new(),
+                max_priority_fee_per_gas: U256::zero(),
+                max_fee_per_blob_gas: U256::zero(),
+                blob_versioned_hashes: Vec::new(),
+                blob_sidecar,
+                authorization_list: AuthorizationList::new(),
+            };
+            if fees == 2 {
+                tx.max_priority_fee_per_gas = rlp.val_at(nonce_at + 1)?;
+            }
+            if tx_type == BLOB_TX_ID {
+                if tx.to.is_none() {
+                    return Err(error::Error::Decoder("blob transaction without recipient".into()));
+                }
+                tx.max_fee_per_blob_gas = rlp.val_at(gas_at + 5)?;
+                tx.blob_versioned_hashes = rlp.list_at(gas_at + 6)?;
+            }
+            if tx_type == SET_CODE_TX_ID {
+                if tx.to.is_none() {
+                    return Err(error::Error::Decoder("set-code transaction without recipient".into()));
+                }
+                for item in rlp.at(gas_at + 5)?.iter() {
+                    if item.item_count()? != 6 {
+                        return Err(rlp::DecoderError::RlpIncorrectListLen.into());
+                    }
+                    tx.authorization_list.push(SignedAuthorization {
+                        chain_id: item.val_at(0)?,
+                        address: item.val_at(1)?,
+                        nonce: item.val_at(2)?,
+                        y_parity: item.val_at(3)?,
+                        r: item.val_at(4)?,
+                        s: item.val_at(5)?,
+                    });
+                }
+                if tx.authorization_list.is_empty() {
+                    return Err(error::Error::Decoder(
+                        "set-code transaction without authorizations".into(),
+                    ));
+                }
+            }
+            if tx_type != LEGACY_TX_ID {
+                for item in rlp.at(gas_at + 4)?.iter() {
+                    tx.access_list.push(AccessListItem {
+                        address: item.val_at(0)?,
+                        storage_keys: item.list_at(1)?,
+                    });
                 }
             }
+
+            let signature_at = expected_items - 3;
+            let signature = Signature {
+                v: rlp.val_at(signature_at)?,
+                r: H256::from_uint(&rlp.val_at(signature_at + 1)?),
+                s: H256::from_uint(&rlp.val_at(signature_at + 2)?),
+            };
+            let chain_id = match tx_type {
+                LEGACY_TX_ID if signature.v >= 35 => Some((signature.v - 35) / 2),
+                LEGACY_TX_ID => None,
+                _ => Some(rlp.val_at(0)?),
+            };
+            Ok((tx, chain_id, signature))
         }
 
         /// Sign and return a raw signed transaction.
-        pub fn sign(self, sign: impl signing::Key, chain_id: u64) -> SignedTransaction {
+        pub fn sign(self, sign: impl signing::Key, chain_id: u64) -> error::Result<SignedTransaction> {
             let adjust_v_value = matches!(self.transaction_type.map(|t| t.as_u64()), Some(LEGACY_TX_ID) | None);
 
-            let encoded = self.encode(chain_id, None);
+            let encoded = self.encode(chain_id, None)?;
 
             let hash = signing::keccak256(encoded.as_ref());
 
             let signature = if adjust_v_value {
-                sign.sign(&hash, Some(chain_id))
-                    .expect("hash is non-zero 32-bytes; qed")
+                sign.sign(&hash, Some(chain_id))?
             } else {
-                sign.sign_message(&hash).expect("hash is non-zero 32-bytes; qed")
+                sign.sign_message(&hash)?
             };
 
-            let signed = self.encode(chain_id, Some(&signature));
+            self.signed(chain_id, hash.into(), signature)
+        }
+
+        /// Encodes the transaction with its signature.
+        fn signed(&self, chain_id: u64, message_hash: H256, signature: Signature) -> error::Result<SignedTransaction> {
+            let mut signed = self.encode(chain_id, Some(&signature))?;
             let transaction_hash = signing::keccak256(signed.as_ref()).into();
+            if let (Some(BLOB_TX_ID), Some(sidecar)) = (self.transaction_type.map(|t| t.as_u64()), &self.blob_sidecar) {
+                signed = blob_network_form(&signed, sidecar);
+            }
 
-            SignedTransaction {
-                message_hash: hash.into(),
+            Ok(SignedTransaction {
+                message_hash,
                 v: signature.v,
                 r: signature.r,
                 s: signature.s,
                 raw_transaction: signed.into(),
                 transaction_hash,
+            })
+        }
+    }
+
+    /// Wraps a signed blob transaction into its network form, followed by the blobs, commitments
+    /// and proofs as defined by EIP-4844.
+    fn blob_network_form(signed: &[u8], sidecar: &BlobSidecar) -> Vec<u8> {
+        let mut stream = RlpStream::new_list(4);
+        stream.append_raw(&signed[1..], 1);
+        for items in [&sidecar.blobs, &sidecar.commitments, &sidecar.proofs] {
+            stream.begin_list(items.len());
+            for item in items {
+                stream.append(&item.0);
             }
         }
+        [&[BLOB_TX_ID as u8], stream.as_raw()].concat()
+    }
+
+    fn unsupported_type(tx_type: u64) -> error::Error {
+        error::Error::Decoder(format!("unsupported transaction type {}", tx_type))
     }
 }
 
-#[cfg(all(test, not(target_arch = "wasm32"), not(feature = "pink")))]
+#[cfg(all(test, feature = "signing"))]
 mod tests {
     use super::*;
     use crate::{
-        signing::{SecretKey, SecretKeyRef},
-        transports::test::TestTransport,
-        types::{Address, Recovery, SignedTransaction, TransactionParameters, U256},
+        keys::secret::SecretKey,
+        rpc::Value,
+        signing::{Key, Signature},
+        transports::test::{block_response, TestTransport},
+        types::{
+            AccessListItem, Address, Authorization, BlobSidecar, Recovery, Replacement, SignedTransaction,
+            TransactionParameters, U256,
+        },
     };
     use accounts_signing::*;
+    use futures::executor::block_on;
     use hex_literal::hex;
+    use rlp::Rlp;
     use serde_json::json;
 
     #[test]
@@ -381,7 +993,7 @@ mod tests {
         let tx = TransactionParameters {
             to: Some(hex!("F0109fC8DF283027b6285cc889F5aA624EaC1F55").into()),
             value: 1_000_000_000.into(),
-            gas: 2_000_000.into(),
+            gas: Some(2_000_000.into()),
             ..Default::default()
         };
         let key = SecretKey::from_slice(&hex!(
@@ -391,7 +1003,7 @@ mod tests {
         let nonce = U256::zero();
         let gas_price = U256::from(21_000_000_000u128);
         let chain_id = "0x1";
-        let from: Address = signing::secret_key_address(&key);
+        let from: Address = key.address();
 
         let mut transport = TestTransport::default();
         transport.add_response(json!(nonce));
@@ -405,10 +1017,10 @@ mod tests {
 
         transport.assert_request(
             "eth_getTransactionCount",
-            &format!(r#"[{:?}, "latest"]"#, json!(from).to_string()),
+            &[json!(from).to_string(), r#""latest""#.into()],
         );
-        transport.assert_request("eth_gasPrice", "[]");
-        transport.assert_request("eth_chainId", "[]");
+        transport.assert_request("eth_gasPrice", &[]);
+        transport.assert_request("eth_chainId", &[]);
         transport.assert_no_more_requests();
 
         let expected = SignedTransaction {
@@ -434,6 +1046,7 @@ mod tests {
         futures::executor::block_on(accounts.sign_transaction(
             TransactionParameters {
                 nonce: Some(0.into()),
+                gas: Some(21_000.into()),
                 gas_price: Some(1.into()),
                 chain_id: Some(42),
                 ..Default::default()
@@ -474,7 +1087,7 @@ mod tests {
             "4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318"
         ))
         .unwrap();
-        let signed = accounts.sign("Some data", SecretKeyRef::new(&key));
+        let signed = accounts.sign("Some data", &key);
 
         assert_eq!(
             signed.message_hash,
@@ -516,7 +1129,7 @@ mod tests {
             "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f"
         ))
         .unwrap();
-        let address: Address = signing::secret_key_address(&key);
+        let address: Address = key.address();
 
         let accounts = Accounts::new(TestTransport::default());
 
@@ -527,6 +1140,7 @@ mod tests {
         let signed = futures::executor::block_on(accounts.sign_transaction(
             TransactionParameters {
                 nonce: Some(0.into()),
+                gas: Some(21_000.into()),
                 gas_price: Some(1u128.into()),
                 chain_id: Some(42),
                 ..Default::default()
@@ -541,6 +1155,39 @@ mod tests {
         accounts.transport().assert_no_more_requests();
     }
 
+    #[test]
+    fn speed_up_signed_transaction_without_lookup() {
+        let key = SecretKey::from_slice(&hex!(
+            "4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318"
+        ))
+        .unwrap();
+        let tx = TransactionParameters {
+            nonce: Some(7.into()),
+            to: Some(Address::from_low_u64_be(0x456)),
+            gas: Some(0x30000.into()),
+            gas_price: Some(1000.into()),
+            value: 100.into(),
+            data: vec![0x12, 0x34].into(),
+            chain_id: Some(5),
+            ..Default::default()
+        };
+        let signed = tx.clone().sign(&key).unwrap();
+        let expected = TransactionParameters {
+            gas_price: Some(1100.into()),
+            ..tx
+        }
+        .sign(&key)
+        .unwrap();
+
+        let mut transport = TestTransport::default();
+        transport.add_response(json!("0x1"));
+        let accounts = Accounts::new(transport.clone());
+        let replacement = futures::executor::block_on(accounts.sign_replacement(&signed, Replacement::SpeedUp, &key));
+        assert_eq!(replacement, Ok(expected));
+        transport.assert_request("eth_gasPrice", &[]);
+        transport.assert_no_more_requests();
+    }
+
     #[test]
     fn sign_transaction_data() {
         // retrieved test vector from:
@@ -556,14 +1203,16 @@ mod tests {
             transaction_type: None,
             access_list: vec![],
             max_priority_fee_per_gas: 0u128.into(),
+            max_fee_per_blob_gas: 0u128.into(),
+            blob_versioned_hashes: vec![],
+            blob_sidecar: None,
+            authorization_list: vec![],
         };
         let skey = SecretKey::from_slice(&hex!(
             "4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318"
         ))
         .unwrap();
-        let key = SecretKeyRef::new(&skey);
-
-        let signed = tx.sign(key, 1);
+        let signed = tx.sign(&skey, 1).unwrap();
 
         let expected = SignedTransaction {
             message_hash: hex!("6893a6ee8df79b0f5d64a180cd1ef35d030f3e296a5361cf04d02ce720d32ec5").into(),
@@ -576,4 +1225,937 @@ mod tests {
 
         assert_eq!(signed, expected);
     }
+
+    fn key() -> SecretKey {
+        SecretKey::from_slice(&hex!(
+            "4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318"
+        ))
+        .unwrap()
+    }
+
+    fn pending(fees: Value) -> Value {
+        let mut tx = json!({
+            "hash": H256::from_low_u64_be(0xaa),
+            "nonce": "0x7",
+            "blockHash": null,
+            "blockNumber": null,
+            "transactionIndex": null,
+            "from": key().address(),
+            "to": Address::from_low_u64_be(0x456),
+            "value": "0x64",
+            "gas": "0x30000",
+            "input": "0x1234"
+        });
+        tx.as_object_mut().unwrap().extend(fees.as_object().unwrap().clone());
+        tx
+    }
+
+    #[test]
+    fn speed_up_legacy_transaction() {
+        let mut transport = TestTransport::default();
+        transport.add_response(pending(json!({ "gasPrice": "0x3e8" })));
+        transport.add_response(json!("0x3e8"));
+
+        let tx = TransactionParameters {
+            nonce: Some(7.into()),
+            to: Some(Address::from_low_u64_be(0x456)),
+            gas: Some(0x30000.into()),
+            gas_price: Some(1100.into()),
+            value: 100.into(),
+            data: vec![0x12, 0x34].into(),
+            chain_id: Some(1),
+            ..Default::default()
+        };
+        let accounts = Accounts::new(transport.clone());
+        let expected = block_on(accounts.sign_transaction(tx, key())).unwrap();
+
+        transport.add_response(json!("0x1"));
+        let signed = block_on(accounts.sign_replacement(H256::from_low_u64_be(0xaa), Replacement::SpeedUp, key()));
+        assert_eq!(signed, Ok(expected));
+
+        transport.assert_request(
+            "eth_getTransactionByHash",
+            &[r#""0x00000000000000000000000000000000000000000000000000000000000000aa""#.into()],
+        );
+        transport.assert_request("eth_gasPrice", &[]);
+        transport.assert_request("eth_chainId", &[]);
+        transport.assert_no_more_requests();
+    }
+
+    #[test]
+    fn cancel_eip1559_transaction() {
+        let mut transport = TestTransport::default();
+        transport.add_response(pending(json!({
+            "type": "0x2",
+            "maxFeePerGas": "0x64",
+            "maxPriorityFeePerGas": "0xa"
+        })));
+        transport.add_response(json!("0xc8"));
+
+        let tx = TransactionParameters {
+            nonce: Some(7.into()),
+            to: Some(key().address()),
+            gas: Some(21_000.into()),
+            value: U256::zero(),
+            chain_id: Some(1),
+            transaction_type: Some(2.into()),
+            max_fee_per_gas: Some(200.into()),
+            max_priority_fee_per_gas: Some(11.into()),
+            ..Default::default()
+        };
+        let accounts = Accounts::new(transport.clone());
+        let expected = block_on(accounts.sign_transaction(tx, key())).unwrap();
+
+        transport.add_response(json!("0x1"));
+        let signed = block_on(accounts.sign_replacement(H256::from_low_u64_be(0xaa), Replacement::Cancel, key()));
+        assert_eq!(signed, Ok(expected));
+    }
+
+    #[test]
+    fn replacing_mined_transaction_fails() {
+        let mut transport = TestTransport::default();
+        let mut tx = pending(json!({ "gasPrice": "0x3e8" }));
+        tx["blockHash"] = json!(H256::from_low_u64_be(0xb));
+        transport.add_response(tx);
+        transport.add_response(json!("0x3e8"));
+
+        let accounts = Accounts::new(transport.clone());
+        let signed = block_on(accounts.sign_replacement(H256::from_low_u64_be(0xaa), Replacement::SpeedUp, key()));
+        assert!(matches!(signed, Err(crate::error::Error::InvalidResponse(_))));
+    }
+
+    #[test]
+    fn replace_blob_transaction() {
+        let mut transport = TestTransport::default();
+        let accounts = Accounts::new(transport.clone());
+        let sidecar = BlobSidecar {
+            blobs: vec![vec![0xaa; 4].into()],
+            commitments: vec![vec![0xbb; 48].into()],
+            proofs: vec![vec![0xcc; 48].into()],
+        };
+        let tx = TransactionParameters {
+            nonce: Some(7.into()),
+            to: Some(Address::from_low_u64_be(0x456)),
+            gas: Some(0x30000.into()),
+            value: 100.into(),
+            data: vec![0x12, 0x34].into(),
+            chain_id: Some(1),
+            transaction_type: Some(3.into()),
+            max_fee_per_gas: Some(100.into()),
+            max_priority_fee_per_gas: Some(10.into()),
+            max_fee_per_blob_gas: Some(6.into()),
+            blob_versioned_hashes: Some(sidecar.versioned_hashes()),
+            ..Default::default()
+        };
+        let pending = pending(json!({
+            "type": "0x3",
+            "maxFeePerGas": "0x64",
+            "maxPriorityFeePerGas": "0xa",
+            "maxFeePerBlobGas": "0x6",
+            "blobVersionedHashes": sidecar.versioned_hashes()
+        }));
+        let hash = H256::from_low_u64_be(0xaa);
+
+        // the node doesn't return the sidecar
+        transport.add_response(pending.clone());
+        transport.add_response(json!("0x64"));
+        assert_eq!(
+            block_on(accounts.sign_replacement(hash, Replacement::SpeedUp, key())),
+            Err(crate::error::Error::MissingParameter("blob_sidecar"))
+        );
+
+        let expected = TransactionParameters {
+            max_fee_per_gas: Some(110.into()),
+            max_priority_fee_per_gas: Some(11.into()),
+            max_fee_per_blob_gas: Some(7.into()),
+            blob_sidecar: Some(sidecar.clone()),
+            ..tx.clone()
+        }
+        .sign(key())
+        .unwrap();
+        transport.add_response(pending.clone());
+        transport.add_response(json!("0x64"));
+        transport.add_response(json!("0x1"));
+        let signed = block_on(accounts.sign_replacement((hash, sidecar.clone()), Replacement::SpeedUp, key())).unwrap();
+        assert_eq!(signed, expected);
+        let decoded = accounts.decode_transaction(&signed.raw_transaction.0).unwrap();
+        assert_eq!(decoded.tx.from, Some(key().address()));
+        assert_eq!(decoded.tx.blob_versioned_hashes, Some(sidecar.versioned_hashes()));
+        assert_eq!(decoded.blob_sidecar, Some(sidecar.clone()));
+
+        // the sidecar of a transaction in network form is kept
+        let original = TransactionParameters {
+            blob_sidecar: Some(sidecar),
+            ..tx
+        }
+        .sign(key())
+        .unwrap();
+        transport.add_response(json!("0x64"));
+        assert_eq!(
+            block_on(accounts.sign_replacement(&original, Replacement::SpeedUp, key())),
+            Ok(expected)
+        );
+
+        let cancel = TransactionParameters {
+            nonce: Some(7.into()),
+            to: Some(key().address()),
+            gas: Some(21_000.into()),
+            chain_id: Some(1),
+            transaction_type: Some(2.into()),
+            max_fee_per_gas: Some(110.into()),
+            max_priority_fee_per_gas: Some(11.into()),
+            ..Default::default()
+        }
+        .sign(key())
+        .unwrap();
+        transport.add_response(pending);
+        transport.add_response(json!("0x64"));
+        transport.add_response(json!("0x1"));
+        assert_eq!(
+            block_on(accounts.sign_replacement(hash, Replacement::Cancel, key())),
+            Ok(cancel)
+        );
+    }
+
+    #[test]
+    fn replace_set_code_transaction() {
+        let mut transport = TestTransport::default();
+        let accounts = Accounts::new(transport.clone());
+        let authorization = accounts.sign_authorization(
+            Authorization {
+                chain_id: 1.into(),
+                address: Address::from_low_u64_be(0x789),
+                nonce: 8.into(),
+            },
+            key(),
+        );
+        let tx = TransactionParameters {
+            nonce: Some(7.into()),
+            to: Some(key().address()),
+            gas: Some(60_000.into()),
+            chain_id: Some(1),
+            transaction_type: Some(4.into()),
+            max_fee_per_gas: Some(100.into()),
+            max_priority_fee_per_gas: Some(10.into()),
+            authorization_list: Some(vec![authorization.clone()]),
+            ..Default::default()
+        };
+        let original = tx.clone().sign(key()).unwrap();
+
+        let expected = TransactionParameters {
+            max_fee_per_gas: Some(110.into()),
+            max_priority_fee_per_gas: Some(11.into()),
+            ..tx
+        }
+        .sign(key())
+        .unwrap();
+        transport.add_response(json!("0x64"));
+        let signed = block_on(accounts.sign_replacement(&original, Replacement::SpeedUp, key())).unwrap();
+        assert_eq!(signed, expected);
+        let decoded = accounts.decode_transaction(&signed.raw_transaction.0).unwrap();
+        assert_eq!(decoded.tx.from, Some(key().address()));
+        assert_eq!(decoded.tx.authorization_list, Some(vec![authorization]));
+
+        let cancel = TransactionParameters {
+            nonce: Some(7.into()),
+            to: Some(key().address()),
+            gas: Some(21_000.into()),
+            chain_id: Some(1),
+            transaction_type: Some(2.into()),
+            max_fee_per_gas: Some(110.into()),
+            max_priority_fee_per_gas: Some(11.into()),
+            ..Default::default()
+        }
+        .sign(key())
+        .unwrap();
+        transport.add_response(json!("0x64"));
+        assert_eq!(
+            block_on(accounts.sign_replacement(&original, Replacement::Cancel, key())),
+            Ok(cancel)
+        );
+        transport.assert_request("eth_gasPrice", &[]);
+        transport.assert_request("eth_gasPrice", &[]);
+        transport.assert_no_more_requests();
+    }
+
+    #[test]
+    fn sign_eip1559_transaction_estimates_fees() {
+        let mut transport = TestTransport::default();
+        let mut block = block_response(0x10, H256::zero(), H256::zero());
+        block["gasLimit"] = json!("0x1c9c380");
+        block["baseFeePerGas"] = json!("0x64");
+        transport.add_response(block);
+        transport.add_response(json!({ "reward": [["0x3"], ["0x7"]] }));
+
+        let tx = TransactionParameters {
+            nonce: Some(0.into()),
+            gas: Some(21_000.into()),
+            chain_id: Some(1),
+            transaction_type: Some(2.into()),
+            ..Default::default()
+        };
+        let accounts = Accounts::new(transport.clone());
+        let signed = block_on(accounts.sign_transaction(tx.clone(), key())).unwrap();
+
+        transport.assert_request("eth_getBlockByNumber", &[r#""latest""#.into(), "false".into()]);
+        transport.assert_request(
+            "eth_feeHistory",
+            &[r#""0xa""#.into(), r#""latest""#.into(), "[50]".into()],
+        );
+        transport.assert_no_more_requests();
+
+        // base fee 100 drops to 88 in an empty block, max fee is twice that plus the median reward
+        let tx = TransactionParameters {
+            max_fee_per_gas: Some(183.into()),
+            max_priority_fee_per_gas: Some(7.into()),
+            ..tx
+        };
+        assert_eq!(block_on(accounts.sign_transaction(tx, key())), Ok(signed));
+    }
+
+    #[test]
+    fn sign_transaction_estimates_gas() {
+        let mut transport = TestTransport::default();
+        transport.add_response(json!("0x5208"));
+
+        let tx = TransactionParameters {
+            nonce: Some(0.into()),
+            to: Some(Address::from_low_u64_be(0x456)),
+            gas_price: Some(1.into()),
+            chain_id: Some(1),
+            gas_multiplier_percent: 150,
+            ..Default::default()
+        };
+        let accounts = Accounts::new(transport.clone());
+        let signed = block_on(accounts.sign_transaction(tx.clone(), key())).unwrap();
+
+        transport.assert_request(
+            "eth_estimateGas",
+            &[
+                r#"{"from":"0x2c7536e3605d9c16a7a3d7b1898e529396a65c23","gasPrice":"0x1","to":"0x0000000000000000000000000000000000000456","data":"0x","value":"0x0"}"#.into(),
+            ],
+        );
+        transport.assert_no_more_requests();
+
+        let tx = TransactionParameters {
+            gas: Some(31_500.into()),
+            ..tx
+        };
+        assert_eq!(block_on(accounts.sign_transaction(tx, key())), Ok(signed));
+    }
+
+    #[test]
+    fn sign_transaction_surfaces_estimation_revert() {
+        let mut transport = TestTransport::default();
+        transport.add_error_with_data(
+            3,
+            "execution reverted: not owner",
+            json!("0x08c379a0000000000000000000000000000000000000000000000000000000000000002000000000000000000000000000000000000000000000000000000000000000096e6f74206f776e65720000000000000000000000000000000000000000000000"),
+        );
+
+        let tx = TransactionParameters {
+            nonce: Some(0.into()),
+            gas_price: Some(1.into()),
+            chain_id: Some(1),
+            ..Default::default()
+        };
+        let accounts = Accounts::new(transport.clone());
+        match block_on(accounts.sign_transaction(tx, key())) {
+            Err(crate::error::Error::Revert(revert)) => {
+                assert_eq!(revert.reason(), Some("not owner".into()));
+                assert_eq!(revert.message, "execution reverted: not owner");
+            }
+            other => panic!("expected a revert, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn decode_legacy_transaction() {
+        // signed in `accounts_sign_transaction`
+        let raw = hex!("f869808504e3b29200831e848094f0109fc8df283027b6285cc889f5aa624eac1f55843b9aca008025a0c9cf86333bcb065d140032ecaab5d9281bde80f21b9687b3e94161de42d51895a0727a108a0b8d101465414033c3f705a9c7b826e596766046ee1183dbc8aeaa68");
+        let accounts = Accounts::new(TestTransport::default());
+        let decoded = accounts.decode_transaction(&raw).unwrap();
+        let tx = decoded.tx;
+
+        assert_eq!(
+            tx.hash,
+            hex!("de8db924885b0803d2edc335f745b2b8750c8848744905684c20b987443a9593").into()
+        );
+        assert_eq!(tx.from, Some(hex!("2c7536E3605D9C16a7a3D7b1898e529396a65c23").into()));
+        assert_eq!(tx.to, Some(hex!("F0109fC8DF283027b6285cc889F5aA624EaC1F55").into()));
+        assert_eq!(tx.nonce, U256::zero());
+        assert_eq!(tx.gas, 2_000_000.into());
+        assert_eq!(tx.gas_price, Some(21_000_000_000u64.into()));
+        assert_eq!(tx.value, 1_000_000_000.into());
+        assert_eq!(decoded.chain_id, Some(1));
+        assert_eq!(tx.v, Some(0x25.into()));
+        assert_eq!(tx.transaction_type, Some(0.into()));
+        assert_eq!(tx.access_list, None);
+        assert_eq!(tx.raw, Some(raw.to_vec().into()));
+    }
+
+    #[test]
+    fn decode_typed_transactions() {
+        let accounts = Accounts::new(TestTransport::default());
+        let access_list = vec![AccessListItem {
+            address: Address::from_low_u64_be(0x789),
+            storage_keys: vec![H256::from_low_u64_be(1)],
+        }];
+
+        let raw = hex!("01f89c05076482520894000000000000000000000000000000000000045605821234f838f7940000000000000000000000000000000000000789e1a0000000000000000000000000000000000000000000000000000000000000000101a06c7646e47a8154ed1bf301b42e2256f09bf2f127b36000a940c1b1f745594192a006d7fa17bb089ef10ccfca4df62e07cf1cfff0629d575ab70b67b82628f92532");
+        let decoded = accounts.decode_transaction(&raw).unwrap();
+        let tx = decoded.tx;
+        assert_eq!(
+            tx.hash,
+            hex!("0ae5df13e320de6e89053c2fec6c2776f314d669379984a53e6f46ae3c54c555").into()
+        );
+        assert_eq!(tx.from, Some(hex!("2c7536E3605D9C16a7a3D7b1898e529396a65c23").into()));
+        assert_eq!(tx.transaction_type, Some(1.into()));
+        assert_eq!(decoded.chain_id, Some(5));
+        assert_eq!(tx.nonce, 7.into());
+        assert_eq!(tx.gas_price, Some(100.into()));
+        assert_eq!(tx.max_fee_per_gas, None);
+        assert_eq!(tx.input, vec![0x12, 0x34].into());
+        assert_eq!(tx.access_list, Some(access_list.clone()));
+
+        let raw = hex!("02f89d0507036482520894000000000000000000000000000000000000045605821234f838f7940000000000000000000000000000000000000789e1a0000000000000000000000000000000000000000000000000000000000000000101a041fbd33a2b365a4f25634a5f7363a1f331406d429e772665c5adaf6f09c9c88ca078d5211c61d73b3b6d7b90991cdf48104a4bb7d0cc3bf11bd7e31b93c79e3f32");
+        let decoded = accounts.decode_transaction(&raw).unwrap();
+        let tx = decoded.tx;
+        assert_eq!(
+            tx.hash,
+            hex!("b07045ff9862ff5d50de104148808ccd4a5089a24a1f6c4ad8ff27b170302853").into()
+        );
+        assert_eq!(tx.from, Some(hex!("2c7536E3605D9C16a7a3D7b1898e529396a65c23").into()));
+        assert_eq!(tx.transaction_type, Some(2.into()));
+        assert_eq!(tx.nonce, 7.into());
+        assert_eq!(tx.gas, 21_000.into());
+        assert_eq!(tx.to, Some(Address::from_low_u64_be(0x456)));
+        assert_eq!(tx.value, 5.into());
+        assert_eq!(tx.gas_price, None);
+        assert_eq!(tx.max_fee_per_gas, Some(100.into()));
+        assert_eq!(tx.max_priority_fee_per_gas, Some(3.into()));
+        assert_eq!(tx.access_list, Some(access_list));
+    }
+
+    #[test]
+    fn decode_blob_transaction_with_sidecar() {
+        let raw = hex!("03f8f1f885050703648252089400000000000000000000000000000000000004560580c009e1a0010657f37554c781402a22917dee2f75def7ab966d7b770905398eba3c44401401a0536c2f59259a6217fe86ca44e3cab418bf1cfbc7115cb39f007b31cf30940641a015237fa165a746ca4c73752b9d0a5267f63360c065f27eba0513e9174a4aef44c584aaaaaaaaf1b0c00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000f1b0c00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000");
+        let accounts = Accounts::new(TestTransport::default());
+        let decoded = accounts.decode_transaction(&raw).unwrap();
+        let tx = decoded.tx;
+
+        // the hash covers the bare transaction only
+        assert_eq!(
+            tx.hash,
+            hex!("94411bfc97414aa9166f846d8ef73357fc4568727835aa51dc04981d79a4462f").into()
+        );
+        assert_eq!(tx.from, Some(hex!("2c7536E3605D9C16a7a3D7b1898e529396a65c23").into()));
+        assert_eq!(tx.transaction_type, Some(3.into()));
+        assert_eq!(decoded.chain_id, Some(5));
+        assert_eq!(tx.max_fee_per_gas, Some(100.into()));
+        assert_eq!(tx.max_priority_fee_per_gas, Some(3.into()));
+        assert_eq!(tx.max_fee_per_blob_gas, Some(9.into()));
+
+        let mut commitment = vec![0; 48];
+        commitment[0] = 0xc0;
+        let sidecar = BlobSidecar {
+            blobs: vec![vec![0xaa; 4].into()],
+            commitments: vec![commitment.clone().into()],
+            proofs: vec![commitment.into()],
+        };
+        assert_eq!(
+            sidecar.versioned_hashes(),
+            vec![hex!("010657f37554c781402a22917dee2f75def7ab966d7b770905398eba3c444014").into()]
+        );
+        assert_eq!(tx.blob_versioned_hashes, Some(sidecar.versioned_hashes()));
+        assert_eq!(decoded.blob_sidecar, Some(sidecar));
+    }
+
+    #[test]
+    fn sign_blob_transaction_estimates_blob_fee() {
+        let mut transport = TestTransport::default();
+        transport.add_response(json!("0x3"));
+
+        let sidecar = BlobSidecar {
+            blobs: vec![vec![0xaa; 4].into()],
+            commitments: vec![vec![0xbb; 48].into()],
+            proofs: vec![vec![0xcc; 48].into()],
+        };
+        let tx = TransactionParameters {
+            nonce: Some(0.into()),
+            to: Some(Address::from_low_u64_be(0x456)),
+            gas: Some(21_000.into()),
+            chain_id: Some(1),
+            transaction_type: Some(3.into()),
+            max_fee_per_gas: Some(100.into()),
+            max_priority_fee_per_gas: Some(1.into()),
+            blob_versioned_hashes: Some(sidecar.versioned_hashes()),
+            ..Default::default()
+        };
+        let accounts = Accounts::new(transport.clone());
+        let bare = block_on(accounts.sign_transaction(tx.clone(), key())).unwrap();
+        transport.assert_request("eth_blobBaseFee", &[]);
+        transport.assert_no_more_requests();
+        assert_eq!(
+            block_on(accounts.sign_transaction(
+                TransactionParameters {
+                    gas: None,
+                    ..tx.clone()
+                },
+                key()
+            )),
+            Err(crate::error::Error::MissingParameter("gas"))
+        );
+        transport.assert_no_more_requests();
+
+        let tx = TransactionParameters {
+            max_fee_per_blob_gas: Some(6.into()),
+            blob_sidecar: Some(sidecar),
+            ..tx
+        };
+        let network = block_on(accounts.sign_transaction(tx, key())).unwrap();
+        assert_eq!(network.transaction_hash, bare.transaction_hash);
+        assert_eq!(network.raw_transaction.0[0], 3);
+        // the bare transaction followed by the blob, commitment and proof lists
+        let body = Rlp::new(&network.raw_transaction.0[1..]);
+        assert_eq!(body.item_count(), Ok(4));
+        assert_eq!(body.at(0).unwrap().as_raw(), &bare.raw_transaction.0[1..]);
+        assert_eq!(body.list_at::<Vec<u8>>(1), Ok(vec![vec![0xaa; 4]]));
+        assert_eq!(body.list_at::<Vec<u8>>(3), Ok(vec![vec![0xcc; 48]]));
+    }
+
+    #[test]
+    fn decode_set_code_transaction() {
+        let raw = hex!("04f8c00507036482ea60942c7536e3605d9c16a7a3d7b1898e529396a65c238080c0f85cf85a059400000000000000000000000000000000000007890801a0b29cc135754ee3f790cec8dd87d4ba4145262d1d54147fe6756d9e4bedf92e0ea03d053862d82bb447691341a5bafd0ba4e9ea2359897a89ba200e6e6ac22ced3401a0cc1ebc80d279353246bd329dcd1f724d0974536afb7228801ea7152f610111cea003b1437498c9608f2aa71719a9f62f0f6def3a858bce3403c89cf7e931bbfef0");
+        let accounts = Accounts::new(TestTransport::default());
+        let tx = accounts.decode_transaction(&raw).unwrap().tx;
+        let sender: Address = hex!("2c7536E3605D9C16a7a3D7b1898e529396a65c23").into();
+
+        assert_eq!(
+            tx.hash,
+            hex!("58017b9512b19cd7cd2abe6ef2ed22dd85afce15e728bdd28664137818e76f10").into()
+        );
+        assert_eq!(tx.from, Some(sender));
+        assert_eq!(tx.to, Some(sender));
+        assert_eq!(tx.transaction_type, Some(4.into()));
+        assert_eq!(tx.gas, 60_000.into());
+
+        let authorizations = tx.authorization_list.unwrap();
+        assert_eq!(authorizations.len(), 1);
+        assert_eq!(
+            authorizations[0].authorization(),
+            Authorization {
+                chain_id: 5.into(),
+                address: Address::from_low_u64_be(0x789),
+                nonce: 8.into(),
+            }
+        );
+        assert_eq!(accounts.recover(&authorizations[0]), Ok(sender));
+    }
+
+    #[test]
+    fn set_code_transaction_requires_recipient_and_authorizations() {
+        use crate::error::Error::{Decoder, MissingParameter};
+
+        let accounts = Accounts::new(TestTransport::default());
+        let authorization = accounts.sign_authorization(
+            Authorization {
+                chain_id: 1.into(),
+                address: Address::from_low_u64_be(0x789),
+                nonce: 0.into(),
+            },
+            key(),
+        );
+        let tx = TransactionParameters {
+            nonce: Some(0.into()),
+            to: Some(key().address()),
+            gas: Some(60_000.into()),
+            chain_id: Some(1),
+            transaction_type: Some(4.into()),
+            max_fee_per_gas: Some(100.into()),
+            max_priority_fee_per_gas: Some(1.into()),
+            authorization_list: Some(vec![authorization.clone()]),
+            ..Default::default()
+        };
+        assert!(tx.clone().sign(key()).is_ok());
+        assert_eq!(
+            block_on(accounts.sign_transaction(
+                TransactionParameters {
+                    gas: None,
+                    ..tx.clone()
+                },
+                key()
+            )),
+            Err(MissingParameter("gas"))
+        );
+        accounts.transport().assert_no_more_requests();
+        assert_eq!(
+            TransactionParameters { to: None, ..tx.clone() }.sign(key()),
+            Err(MissingParameter("to"))
+        );
+        assert_eq!(
+            TransactionParameters {
+                authorization_list: Some(vec![]),
+                ..tx
+            }
+            .sign(key()),
+            Err(MissingParameter("authorization_list"))
+        );
+
+        let unchecked = |to, authorization_list| accounts_signing::Transaction {
+            to,
+            nonce: 0.into(),
+            gas: 60_000.into(),
+            gas_price: 100.into(),
+            value: 0.into(),
+            data: vec![],
+            transaction_type: Some(4.into()),
+            access_list: vec![],
+            max_priority_fee_per_gas: 1.into(),
+            max_fee_per_blob_gas: 0.into(),
+            blob_versioned_hashes: vec![],
+            blob_sidecar: None,
+            authorization_list,
+        };
+        let raw = unchecked(None, vec![authorization])
+            .sign(key(), 1)
+            .unwrap()
+            .raw_transaction;
+        assert_eq!(
+            accounts.decode_transaction(&raw.0),
+            Err(Decoder("set-code transaction without recipient".into()))
+        );
+        let raw = unchecked(Some(key().address()), vec![])
+            .sign(key(), 1)
+            .unwrap()
+            .raw_transaction;
+        assert_eq!(
+            accounts.decode_transaction(&raw.0),
+            Err(Decoder("set-code transaction without authorizations".into()))
+        );
+    }
+
+    #[test]
+    fn sign_authorization() {
+        let accounts = Accounts::new(TestTransport::default());
+        let auth = accounts.sign_authorization(
+            Authorization {
+                chain_id: 1.into(),
+                address: Address::from_low_u64_be(0x789),
+                nonce: 3.into(),
+            },
+            key(),
+        );
+        assert_eq!(
+            serde_json::to_value(&auth).unwrap(),
+            json!({
+                "chainId": "0x1",
+                "address": "0x0000000000000000000000000000000000000789",
+                "nonce": "0x3",
+                "yParity": "0x0",
+                "r": "0x32e0dacce8d81d63628670a4a04ea8a3ba9e730ab07c2c2585b69c9ee18e1050",
+                "s": "0x10c03a9d504e75802d9cd2042bf137a4e8c827b3c6f0ecc6041125a7d6cd6fcd"
+            })
+        );
+        assert_eq!(accounts.recover(&auth), Ok(key().address()));
+    }
+
+    #[test]
+    fn sign_offline() {
+        let accounts = Accounts::new(TestTransport::default());
+        let legacy = TransactionParameters {
+            nonce: Some(3.into()),
+            to: Some(Address::from_low_u64_be(0x456)),
+            gas: Some(21_000.into()),
+            gas_price: Some(10.into()),
+            chain_id: Some(1),
+            ..Default::default()
+        };
+        let eip1559 = TransactionParameters {
+            gas_price: None,
+            transaction_type: Some(2.into()),
+            max_fee_per_gas: Some(20.into()),
+            max_priority_fee_per_gas: Some(2.into()),
+            ..legacy.clone()
+        };
+        for tx in [legacy, eip1559] {
+            let signed = tx.clone().sign(key()).unwrap();
+            assert_eq!(block_on(accounts.sign_transaction(tx, key())), Ok(signed));
+        }
+        accounts.transport().assert_no_more_requests();
+    }
+
+    #[test]
+    fn sign_offline_requires_all_parameters() {
+        use crate::error::Error::MissingParameter;
+
+        let tx = TransactionParameters {
+            nonce: Some(0.into()),
+            gas: Some(21_000.into()),
+            gas_price: Some(1.into()),
+            chain_id: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(
+            TransactionParameters {
+                nonce: None,
+                ..tx.clone()
+            }
+            .sign(key()),
+            Err(MissingParameter("nonce"))
+        );
+        assert_eq!(
+            TransactionParameters {
+                gas: None,
+                ..tx.clone()
+            }
+            .sign(key()),
+            Err(MissingParameter("gas"))
+        );
+        assert_eq!(
+            TransactionParameters {
+                chain_id: None,
+                ..tx.clone()
+            }
+            .sign(key()),
+            Err(MissingParameter("chain_id"))
+        );
+        assert_eq!(
+            TransactionParameters {
+                gas_price: None,
+                ..tx.clone()
+            }
+            .sign(key()),
+            Err(MissingParameter("gas_price"))
+        );
+
+        let eip1559 = TransactionParameters {
+            transaction_type: Some(2.into()),
+            ..tx
+        };
+        assert_eq!(
+            eip1559.clone().sign(key()),
+            Err(MissingParameter("max_priority_fee_per_gas"))
+        );
+        let blob = TransactionParameters {
+            transaction_type: Some(3.into()),
+            max_priority_fee_per_gas: Some(1.into()),
+            ..eip1559
+        };
+        assert_eq!(blob.sign(key()), Err(MissingParameter("max_fee_per_blob_gas")));
+    }
+
+    #[test]
+    fn sign_offline_validates_blob_transactions() {
+        use crate::error::Error::{InvalidParameter, MissingParameter};
+
+        let sidecar = BlobSidecar {
+            blobs: vec![vec![0xaa; 4].into()],
+            commitments: vec![vec![0xbb; 48].into()],
+            proofs: vec![vec![0xcc; 48].into()],
+        };
+        let tx = TransactionParameters {
+            nonce: Some(0.into()),
+            to: Some(Address::from_low_u64_be(0x456)),
+            gas: Some(21_000.into()),
+            chain_id: Some(1),
+            transaction_type: Some(3.into()),
+            max_fee_per_gas: Some(100.into()),
+            max_priority_fee_per_gas: Some(1.into()),
+            max_fee_per_blob_gas: Some(6.into()),
+            blob_sidecar: Some(sidecar.clone()),
+            ..Default::default()
+        };
+        assert!(tx.clone().sign(key()).is_ok());
+        assert!(TransactionParameters {
+            blob_versioned_hashes: Some(sidecar.versioned_hashes()),
+            ..tx.clone()
+        }
+        .sign(key())
+        .is_ok());
+
+        assert_eq!(
+            TransactionParameters { to: None, ..tx.clone() }.sign(key()),
+            Err(MissingParameter("to"))
+        );
+        assert_eq!(
+            TransactionParameters {
+                blob_sidecar: None,
+                ..tx.clone()
+            }
+            .sign(key()),
+            Err(MissingParameter("blob_versioned_hashes"))
+        );
+        assert_eq!(
+            TransactionParameters {
+                blob_versioned_hashes: Some(vec![]),
+                blob_sidecar: None,
+                ..tx.clone()
+            }
+            .sign(key()),
+            Err(MissingParameter("blob_versioned_hashes"))
+        );
+        assert_eq!(
+            TransactionParameters {
+                blob_versioned_hashes: Some(vec![H256::from_low_u64_be(1)]),
+                ..tx
+            }
+            .sign(key()),
+            Err(InvalidParameter("blob_versioned_hashes"))
+        );
+    }
+
+    #[test]
+    fn decode_rejects_trailing_bytes_and_blob_without_recipient() {
+        use crate::error::Error::Decoder;
+
+        let accounts = Accounts::new(TestTransport::default());
+        // signed in `accounts_sign_transaction` and `decode_typed_transactions`
+        let legacy = hex!("f869808504e3b29200831e848094f0109fc8df283027b6285cc889f5aa624eac1f55843b9aca008025a0c9cf86333bcb065d140032ecaab5d9281bde80f21b9687b3e94161de42d51895a0727a108a0b8d101465414033c3f705a9c7b826e596766046ee1183dbc8aeaa68");
+        let typed = hex!("02f89d0507036482520894000000000000000000000000000000000000045605821234f838f7940000000000000000000000000000000000000789e1a0000000000000000000000000000000000000000000000000000000000000000101a041fbd33a2b365a4f25634a5f7363a1f331406d429e772665c5adaf6f09c9c88ca078d5211c61d73b3b6d7b90991cdf48104a4bb7d0cc3bf11bd7e31b93c79e3f32");
+        for raw in [&legacy[..], &typed[..]] {
+            assert!(accounts.decode_transaction(raw).is_ok());
+            assert_eq!(
+                accounts.decode_transaction(&[raw, &[0x80]].concat()),
+                Err(Decoder("trailing bytes after the transaction".into()))
+            );
+        }
+
+        let unchecked = accounts_signing::Transaction {
+            to: None,
+            nonce: 0.into(),
+            gas: 21_000.into(),
+            gas_price: 100.into(),
+            value: 0.into(),
+            data: vec![],
+            transaction_type: Some(3.into()),
+            access_list: vec![],
+            max_priority_fee_per_gas: 1.into(),
+            max_fee_per_blob_gas: 6.into(),
+            blob_versioned_hashes: vec![H256::from_low_u64_be(1)],
+            blob_sidecar: None,
+            authorization_list: vec![],
+        };
+        let raw = unchecked.sign(key(), 1).unwrap().raw_transaction;
+        assert_eq!(
+            accounts.decode_transaction(&raw.0),
+            Err(Decoder("blob transaction without recipient".into()))
+        );
+    }
+
+    #[test]
+    fn attach_external_signature() {
+        let sender: Address = hex!("2c7536E3605D9C16a7a3D7b1898e529396a65c23").into();
+        let signature = |v| Signature {
+            v,
+            r: hex!("41fbd33a2b365a4f25634a5f7363a1f331406d429e772665c5adaf6f09c9c88c").into(),
+            s: hex!("78d5211c61d73b3b6d7b90991cdf48104a4bb7d0cc3bf11bd7e31b93c79e3f32").into(),
+        };
+        // signed in `decode_typed_transactions`
+        let tx = TransactionParameters {
+            nonce: Some(7.into()),
+            to: Some(Address::from_low_u64_be(0x456)),
+            gas: Some(21_000.into()),
+            value: 5.into(),
+            data: vec![0x12, 0x34].into(),
+            chain_id: Some(5),
+            transaction_type: Some(2.into()),
+            access_list: Some(vec![AccessListItem {
+                address: Address::from_low_u64_be(0x789),
+                storage_keys: vec![H256::from_low_u64_be(1)],
+            }]),
+            max_fee_per_gas: Some(100.into()),
+            max_priority_fee_per_gas: Some(3.into()),
+            ..Default::default()
+        };
+
+        let unsigned = tx.clone().unsigned(sender).unwrap();
+        assert_eq!(unsigned.from(), sender);
+        assert_eq!(
+            unsigned.signing_hash(),
+            crate::signing::keccak256(&unsigned.payload().0).into()
+        );
+        let signed = unsigned.clone().attach_signature(signature(1)).unwrap();
+        assert_eq!(signed.v, 1);
+        assert_eq!(signed.message_hash, unsigned.signing_hash());
+        assert_eq!(
+            signed.transaction_hash,
+            hex!("b07045ff9862ff5d50de104148808ccd4a5089a24a1f6c4ad8ff27b170302853").into()
+        );
+        assert_eq!(unsigned.clone().attach_signature(signature(28)), Ok(signed));
+
+        let invalid = Err(crate::error::Error::Recovery(
+            crate::signing::RecoveryError::InvalidSignature,
+        ));
+        assert_eq!(unsigned.attach_signature(signature(0)), invalid);
+        let other = tx.unsigned(Address::from_low_u64_be(0x123)).unwrap();
+        assert_eq!(other.attach_signature(signature(1)), invalid);
+    }
+
+    #[test]
+    fn prepare_legacy_transaction() {
+        let sender: Address = hex!("2c7536E3605D9C16a7a3D7b1898e529396a65c23").into();
+        let mut transport = TestTransport::default();
+        transport.add_response(json!("0x0"));
+
+        // signed in `accounts_sign_transaction`
+        let tx = TransactionParameters {
+            to: Some(hex!("F0109fC8DF283027b6285cc889F5aA624EaC1F55").into()),
+            value: 1_000_000_000.into(),
+            gas: Some(2_000_000.into()),
+            gas_price: Some(21_000_000_000u64.into()),
+            chain_id: Some(1),
+            ..Default::default()
+        };
+        let accounts = Accounts::new(transport.clone());
+        let unsigned = block_on(accounts.prepare_transaction(tx, sender)).unwrap();
+        transport.assert_request(
+            "eth_getTransactionCount",
+            &[
+                r#""0x2c7536e3605d9c16a7a3d7b1898e529396a65c23""#.into(),
+                r#""latest""#.into(),
+            ],
+        );
+        transport.assert_no_more_requests();
+
+        let signed = unsigned
+            .attach_signature(Signature {
+                v: 0x25,
+                r: hex!("c9cf86333bcb065d140032ecaab5d9281bde80f21b9687b3e94161de42d51895").into(),
+                s: hex!("727a108a0b8d101465414033c3f705a9c7b826e596766046ee1183dbc8aeaa68").into(),
+            })
+            .unwrap();
+        assert_eq!(signed.v, 0x25);
+        assert_eq!(signed.raw_transaction.0, hex!("f869808504e3b29200831e848094f0109fc8df283027b6285cc889f5aa624eac1f55843b9aca008025a0c9cf86333bcb065d140032ecaab5d9281bde80f21b9687b3e94161de42d51895a0727a108a0b8d101465414033c3f705a9c7b826e596766046ee1183dbc8aeaa68"));
+    }
+
+    #[test]
+    fn unsupported_transaction_type() {
+        let accounts = Accounts::new(TestTransport::default());
+        assert_eq!(
+            accounts.decode_transaction(&[0x7f, 0xc0]),
+            Err(crate::error::Error::Decoder("unsupported transaction type 127".into()))
+        );
+        assert!(matches!(
+            accounts.decode_transaction(&[]),
+            Err(crate::error::Error::Decoder(_))
+        ));
+        assert!(matches!(
+            accounts.decode_transaction(&hex!("02c3010203")),
+            Err(crate::error::Error::Decoder(_))
+        ));
+
+        let tx = TransactionParameters {
+            nonce: Some(0.into()),
+            gas: Some(21_000.into()),
+            gas_price: Some(1.into()),
+            chain_id: Some(1),
+            transaction_type: Some(0x7f.into()),
+            ..Default::default()
+        };
+        assert_eq!(
+            block_on(accounts.sign_transaction(tx, key())),
+            Err(crate::error::Error::Decoder("unsupported transaction type 127".into()))
+        );
+    }
 }
@@ -0,0 +1,272 @@
+//! Development node namespace (`evm_*`, `anvil_*` and `hardhat_*` methods)
+
+use crate::prelude::*;
+use crate::{
+    api::Namespace,
+    helpers::{self, CallFuture},
+    types::{Address, Bytes, H256, U256, U64},
+    Transport,
+};
+use serde::Serialize;
+
+/// Method set understood by the development node.
+///
+/// Anvil accepts both the `anvil_*` and `hardhat_*` names, Hardhat Network only the latter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DevFlavor {
+    /// Use `anvil_*` method names.
+    #[default]
+    Anvil,
+    /// Use `hardhat_*` method names.
+    Hardhat,
+}
+
+/// Fork configuration used by [`Dev::reset`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Forking {
+    /// URL of the node to fork from.
+    pub json_rpc_url: String,
+    /// Block to fork from (None for latest).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_number: Option<u64>,
+}
+
+#[derive(Serialize)]
+struct ResetParams<'a> {
+    forking: &'a Forking,
+}
+
+/// Development node namespace, for local test chains such as anvil or hardhat.
+///
+/// Not registered on `Web3` directly, use `web3.api::<Dev<_>>()`.
+#[derive(Debug, Clone)]
+pub struct Dev<T> {
+    transport: T,
+    flavor: DevFlavor,
+}
+
+impl<T: Transport> Namespace<T> for Dev<T> {
+    fn new(transport: T) -> Self
+    where
+        Self: Sized,
+    {
+        Dev {
+            transport,
+            flavor: DevFlavor::default(),
+        }
+    }
+
+    fn transport(&self) -> &T {
+        &self.transport
+    }
+}
+
+impl<T: Transport> Dev<T> {
+    /// Switches the method names used for the `anvil_*`/`hardhat_*` calls.
+    pub fn with_flavor(mut self, flavor: DevFlavor) -> Self {
+        self.flavor = flavor;
+        self
+    }
+
+    fn method(&self, anvil: &'static str, hardhat: &'static str) -> &'static str {
+        match self.flavor {
+            DevFlavor::Anvil => anvil,
+            DevFlavor::Hardhat => hardhat,
+        }
+    }
+
+    /// Snapshot the state of the blockchain at the current block. Returns the snapshot id.
+    pub fn snapshot(&self) -> CallFuture<U256, T::Out> {
+        CallFuture::new(self.transport.execute("evm_snapshot", vec![]))
+    }
+
+    /// Revert the state of the blockchain to a previous snapshot.
+    ///
+    /// Returns false if the snapshot doesn't exist. A snapshot can only be reverted once.
+    pub fn revert(&self, snapshot_id: U256) -> CallFuture<bool, T::Out> {
+        let snapshot_id = helpers::serialize(&snapshot_id);
+        CallFuture::new(self.transport.execute("evm_revert", vec![snapshot_id]))
+    }
+
+    /// Mine a single block, optionally with the given timestamp.
+    pub fn mine(&self, timestamp: Option<u64>) -> CallFuture<String, T::Out> {
+        let args = match timestamp.as_ref() {
+            Some(timestamp) => vec![helpers::serialize(timestamp)],
+            None => vec![],
+        };
+        CallFuture::new(self.transport.execute("evm_mine", args))
+    }
+
+    /// Jump forward in time by the given amount of seconds. Returns the total time adjustment.
+    pub fn increase_time(&self, seconds: u64) -> CallFuture<i64, T::Out> {
+        let seconds = helpers::serialize(&seconds);
+        CallFuture::new(self.transport.execute("evm_increaseTime", vec![seconds]))
+    }
+
+    /// Set the balance of the given account.
+    pub fn set_balance(&self, address: Address, balance: U256) -> CallFuture<(), T::Out> {
+        let address = helpers::serialize(&address);
+        let balance = helpers::serialize(&balance);
+        CallFuture::new(self.transport.execute(
+            self.method("anvil_setBalance", "hardhat_setBalance"),
+            vec![address, balance],
+        ))
+    }
+
+    /// Set the nonce of the given account.
+    pub fn set_nonce(&self, address: Address, nonce: U64) -> CallFuture<(), T::Out> {
+        let address = helpers::serialize(&address);
+        let nonce = helpers::serialize(&nonce);
+        CallFuture::new(
+            self.transport
+                .execute(self.method("anvil_setNonce", "hardhat_setNonce"), vec![address, nonce]),
+        )
+    }
+
+    /// Replace the code of the given account.
+    pub fn set_code(&self, address: Address, code: Bytes) -> CallFuture<(), T::Out> {
+        let address = helpers::serialize(&address);
+        let code = helpers::serialize(&code);
+        CallFuture::new(
+            self.transport
+                .execute(self.method("anvil_setCode", "hardhat_setCode"), vec![address, code]),
+        )
+    }
+
+    /// Write a single storage slot of the given account.
+    pub fn set_storage_at(&self, address: Address, slot: U256, value: H256) -> CallFuture<bool, T::Out> {
+        let address = helpers::serialize(&address);
+        let slot = helpers::serialize(&slot);
+        let value = helpers::serialize(&value);
+        CallFuture::new(self.transport.execute(
+            self.method("anvil_setStorageAt", "hardhat_setStorageAt"),
+            vec![address, slot, value],
+        ))
+    }
+
+    /// Allow sending transactions from the given account without its private key.
+    pub fn impersonate_account(&self, address: Address) -> CallFuture<(), T::Out> {
+        let address = helpers::serialize(&address);
+        CallFuture::new(self.transport.execute(
+            self.method("anvil_impersonateAccount", "hardhat_impersonateAccount"),
+            vec![address],
+        ))
+    }
+
+    /// Stop impersonating the given account.
+    pub fn stop_impersonating_account(&self, address: Address) -> CallFuture<(), T::Out> {
+        let address = helpers::serialize(&address);
+        CallFuture::new(self.transport.execute(
+            self.method("anvil_stopImpersonatingAccount", "hardhat_stopImpersonatingAccount"),
+            vec![address],
+        ))
+    }
+
+    /// Reset the chain, optionally re-forking from the given node.
+    pub fn reset(&self, forking: Option<Forking>) -> CallFuture<(), T::Out> {
+        let method = self.method("anvil_reset", "hardhat_reset");
+        let result = match forking.as_ref() {
+            Some(forking) => {
+                let params = ResetParams { forking };
+                let params = helpers::serialize(&params);
+                self.transport.execute(method, vec![params])
+            }
+            None => self.transport.execute(method, vec![]),
+        };
+        CallFuture::new(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Dev, DevFlavor, Forking};
+    use crate::{
+        api::Namespace,
+        rpc::Value,
+        transports::test::TestTransport,
+        types::{Address, H256},
+    };
+
+    rpc_test! (
+      Dev:snapshot => "evm_snapshot";
+      Value::String("0x1".into()) => 1
+    );
+
+    rpc_test! (
+      Dev:revert, 1 => "evm_revert", vec![r#""0x1""#];
+      Value::Bool(true) => true
+    );
+
+    rpc_test! (
+      Dev:mine, None => "evm_mine", Vec::<String>::new();
+      Value::String("0x0".into()) => "0x0"
+    );
+
+    rpc_test! (
+      Dev:mine:mine_with_timestamp, Some(1_700_000_000u64) => "evm_mine", vec!["1700000000"];
+      Value::String("0x0".into()) => "0x0"
+    );
+
+    rpc_test! (
+      Dev:increase_time, 3600u64 => "evm_increaseTime", vec!["3600"];
+      Value::Number(3600.into()) => 3600
+    );
+
+    rpc_test! (
+      Dev:set_balance, Address::from_low_u64_be(0x123), 0x10
+      =>
+      "anvil_setBalance", vec![r#""0x0000000000000000000000000000000000000123""#, r#""0x10""#];
+      Value::Null => ()
+    );
+
+    rpc_test! (
+      Dev:set_code, Address::from_low_u64_be(0x123), vec![0x60, 0x00]
+      =>
+      "anvil_setCode", vec![r#""0x0000000000000000000000000000000000000123""#, r#""0x6000""#];
+      Value::Null => ()
+    );
+
+    rpc_test! (
+      Dev:set_storage_at, Address::from_low_u64_be(0x123), 0x1, H256::from_low_u64_be(0x2)
+      =>
+      "anvil_setStorageAt", vec![
+        r#""0x0000000000000000000000000000000000000123""#,
+        r#""0x1""#,
+        r#""0x0000000000000000000000000000000000000000000000000000000000000002""#
+      ];
+      Value::Bool(true) => true
+    );
+
+    rpc_test! (
+      Dev:impersonate_account, Address::from_low_u64_be(0x123)
+      =>
+      "anvil_impersonateAccount", vec![r#""0x0000000000000000000000000000000000000123""#];
+      Value::Null => ()
+    );
+
+    rpc_test! (
+      Dev:reset, Some(Forking { json_rpc_url: "http://localhost:8545".into(), block_number: Some(100) })
+      =>
+      "anvil_reset", vec![r#"{"forking":{"jsonRpcUrl":"http://localhost:8545","blockNumber":100}}"#];
+      Value::Null => ()
+    );
+
+    #[test]
+    fn hardhat_aliases() {
+        let mut transport = TestTransport::default();
+        transport.add_response(Value::Null);
+        transport.add_response(Value::Null);
+
+        let dev = Dev::new(&transport).with_flavor(DevFlavor::Hardhat);
+        futures::executor::block_on(dev.impersonate_account(Address::from_low_u64_be(0x123))).unwrap();
+        futures::executor::block_on(dev.reset(None)).unwrap();
+
+        transport.assert_request(
+            "hardhat_impersonateAccount",
+            &[r#""0x0000000000000000000000000000000000000123""#.into()],
+        );
+        transport.assert_request("hardhat_reset", &[]);
+        transport.assert_no_more_requests();
+    }
+}
@@ -6,8 +6,9 @@ use crate::{
     api::Namespace,
     helpers::{self, CallFuture},
     types::{
-        Address, Block, BlockHeader, BlockId, BlockNumber, Bytes, CallRequest, FeeHistory, Filter, Index, Log, Proof,
-        Transaction, TransactionId, TransactionReceipt, TransactionRequest, Work, H256, H520, H64, U256, U64,
+        Address, Block, BlockHeader, BlockId, BlockNumber, BlockOverrides, Bytes, CallRequest, FeeHistory, Filter,
+        Index, Log, Proof, RawTransaction, SimulatePayload, SimulatedBlock, StateOverride, Transaction, TransactionId,
+        TransactionReceipt, TransactionRequest, Work, H256, H520, H64, U256, U64,
     },
     Transport,
 };
@@ -59,7 +60,48 @@ impl<T: Transport> Eth<T> {
         let block = block.unwrap_or_else(|| BlockNumber::Latest.into());
         let block = helpers::serialize(&block);
 
-        CallFuture::new(self.transport.execute("eth_call", vec![req, block]))
+        CallFuture::with_reverts(self.transport.execute("eth_call", vec![req, block]))
+    }
+
+    /// Call a constant method of contract with the given account state and block header overridden.
+    ///
+    /// The overrides only apply for the duration of the call. Block overrides are a geth extension
+    /// and are only sent when provided.
+    pub fn call_with_overrides(
+        &self,
+        req: CallRequest,
+        block: Option<BlockId>,
+        state_overrides: Option<StateOverride>,
+        block_overrides: Option<BlockOverrides>,
+    ) -> CallFuture<Bytes, T::Out> {
+        let req = helpers::serialize(&req);
+        let block = block.unwrap_or_else(|| BlockNumber::Latest.into());
+        let block = helpers::serialize(&block);
+        let state_overrides = state_overrides.unwrap_or_default();
+        let state_overrides = helpers::serialize(&state_overrides);
+
+        let args = match block_overrides.as_ref() {
+            Some(block_overrides) => vec![req, block, state_overrides, helpers::serialize(block_overrides)],
+            None => vec![req, block, state_overrides],
+        };
+
+        CallFuture::with_reverts(self.transport.execute("eth_call", args))
+    }
+
+    /// Simulate a sequence of blocks and calls on top of the given block using `eth_simulateV1`.
+    ///
+    /// Each call sees the state changes of the calls before it, so dependent transactions can be
+    /// simulated before any of them is signed.
+    pub fn simulate(
+        &self,
+        payload: SimulatePayload,
+        block: Option<BlockId>,
+    ) -> CallFuture<Vec<SimulatedBlock>, T::Out> {
+        let payload = helpers::serialize(&payload);
+        let block = block.unwrap_or_else(|| BlockNumber::Latest.into());
+        let block = helpers::serialize(&block);
+
+        CallFuture::new(self.transport.execute("eth_simulateV1", vec![payload, block]))
     }
 
     /// Get coinbase address
@@ -94,7 +136,7 @@ impl<T: Transport> Eth<T> {
             None => vec![req],
         };
 
-        CallFuture::new(self.transport.execute("eth_estimateGas", args))
+        CallFuture::with_reverts(self.transport.execute("eth_estimateGas", args))
     }
 
     /// Get current recommended gas price
@@ -102,6 +144,11 @@ impl<T: Transport> Eth<T> {
         CallFuture::new(self.transport.execute("eth_gasPrice", vec![]))
     }
 
+    /// Get the base fee per blob gas of the next block
+    pub fn blob_base_fee(&self) -> CallFuture<U256, T::Out> {
+        CallFuture::new(self.transport.execute("eth_blobBaseFee", vec![]))
+    }
+
     /// Returns a collection of historical gas information. This can be used for evaluating the max_fee_per_gas
     /// and max_priority_fee_per_gas to send the future transactions.
     pub fn fee_history(
@@ -361,6 +408,12 @@ impl<T: Transport> Eth<T> {
         CallFuture::new(self.transport.execute("eth_sign", vec![address, data]))
     }
 
+    /// Signs a transaction with an account of the node, without sending it
+    pub fn sign_transaction(&self, tx: TransactionRequest) -> CallFuture<RawTransaction, T::Out> {
+        let tx = helpers::serialize(&tx);
+        CallFuture::new(self.transport.execute("eth_signTransaction", vec![tx]))
+    }
+
     /// Submit hashrate of external miner
     pub fn submit_hashrate(&self, rate: U256, id: H256) -> CallFuture<bool, T::Out> {
         let rate = helpers::serialize(&rate);
@@ -406,9 +459,10 @@ mod tests {
         api::Namespace,
         rpc::Value,
         types::{
-            Address, Block, BlockHeader, BlockId, BlockNumber, CallRequest, FeeHistory, FilterBuilder, Log, Proof,
-            SyncInfo, SyncState, Transaction, TransactionId, TransactionReceipt, TransactionRequest, Work, H256, H520,
-            H64, U256,
+            AccountOverride, Address, Block, BlockHeader, BlockId, BlockNumber, BlockOverrides, CallRequest,
+            FeeHistory, FilterBuilder, Log, Proof, RawTransaction, SimulateBlock, SimulatePayload, SimulatedBlock,
+            StateOverride, SyncInfo, SyncState, Transaction, TransactionId, TransactionReceipt, TransactionRequest,
+            Work, H256, H520, H64, U256,
         },
     };
     use hex_literal::hex;
@@ -523,6 +577,23 @@ mod tests {
     "effectiveGasPrice": "0x100"
   }"#;
 
+    const EXAMPLE_RAW_TX: &str = r#"{
+    "raw": "0x01020304",
+    "tx": {
+      "hash": "0x0000000000000000000000000000000000000000000000000000000000000123",
+      "nonce": "0x0",
+      "blockHash": null,
+      "blockNumber": null,
+      "transactionIndex": null,
+      "from": "0x0000000000000000000000000000000000000123",
+      "to": "0x0000000000000000000000000000000000000123",
+      "value": "0x1",
+      "gas": "0x5208",
+      "gasPrice": "0x1",
+      "input": "0x"
+    }
+  }"#;
+
     const EXAMPLE_FEE_HISTORY: &str = r#"{
       "baseFeePerGas": [
           "0x15f794d04b",
@@ -542,6 +613,23 @@ mod tests {
       "oldestBlock": "0xcd1df9"
   }"#;
 
+    const EXAMPLE_SIMULATE_RESULT: &str = r#"[{
+      "number": "0x1b5",
+      "hash": "0x0e670ec64341771606e55d6b4ca35a1a6b75ee3d5145a99d05921026d1527331",
+      "parentHash": "0x9646252be9520f6e71339a8df9c55e4d7619deeb018d2a3f2d21fc165dde5eb5",
+      "timestamp": "0x54e34e9a",
+      "gasLimit": "0x1c9c380",
+      "gasUsed": "0x5208",
+      "baseFeePerGas": "0x7",
+      "transactions": [],
+      "calls": [{
+        "returnData": "0x",
+        "logs": [],
+        "gasUsed": "0x5208",
+        "status": "0x1"
+      }]
+  }]"#;
+
     ///taken from RPC docs
     /// https://eips.ethereum.org/EIPS/eip-1186
     const EXAMPLE_PROOF: &str = r#"{
@@ -592,6 +680,55 @@ mod tests {
       Value::String("0x010203".into()) => hex!("010203")
     );
 
+    rpc_test! (
+      Eth:call_with_overrides, CallRequest {
+        to: Some(Address::from_low_u64_be(0x123)),
+        ..Default::default()
+      }, None, Some(StateOverride::from([(
+        Address::from_low_u64_be(0x456),
+        AccountOverride::default().balance(0x10.into()),
+      )])), None
+      =>
+      "eth_call", vec![
+        r#"{"to":"0x0000000000000000000000000000000000000123"}"#,
+        r#""latest""#,
+        r#"{"0x0000000000000000000000000000000000000456":{"balance":"0x10"}}"#
+      ];
+      Value::String("0x010203".into()) => hex!("010203")
+    );
+
+    rpc_test! (
+      Eth:call_with_overrides:call_with_block_overrides, CallRequest {
+        to: Some(Address::from_low_u64_be(0x123)),
+        ..Default::default()
+      }, Some(BlockNumber::Pending.into()), None, Some(BlockOverrides {
+        number: Some(0x10.into()),
+        ..Default::default()
+      })
+      =>
+      "eth_call", vec![
+        r#"{"to":"0x0000000000000000000000000000000000000123"}"#,
+        r#""pending""#,
+        r#"{}"#,
+        r#"{"number":"0x10"}"#
+      ];
+      Value::String("0x".into()) => hex!("")
+    );
+
+    rpc_test! (
+      Eth:simulate, SimulatePayload::default().block(SimulateBlock::default().call(CallRequest {
+        to: Some(Address::from_low_u64_be(0x123)),
+        ..Default::default()
+      })), None
+      =>
+      "eth_simulateV1", vec![
+        r#"{"blockStateCalls":[{"calls":[{"to":"0x0000000000000000000000000000000000000123"}]}]}"#,
+        r#""latest""#
+      ];
+      ::serde_json::from_str(EXAMPLE_SIMULATE_RESULT).unwrap()
+      => ::serde_json::from_str::<Vec<SimulatedBlock>>(EXAMPLE_SIMULATE_RESULT).unwrap()
+    );
+
     rpc_test! (
       Eth:coinbase => "eth_coinbase";
       Value::String("0x0000000000000000000000000000000000000123".into()) => Address::from_low_u64_be(0x123)
@@ -656,6 +793,11 @@ mod tests {
       Value::String("0x123".into()) => 0x123
     );
 
+    rpc_test! (
+      Eth:blob_base_fee => "eth_blobBaseFee";
+      Value::String("0x1".into()) => 0x1
+    );
+
     rpc_test! (
       Eth:fee_history, 0x3, BlockNumber::Latest, None => "eth_feeHistory", vec![r#""0x3""#, r#""latest""#, r#"null"#];
       ::serde_json::from_str(EXAMPLE_FEE_HISTORY).unwrap()
@@ -903,6 +1045,21 @@ mod tests {
       Value::String("0x0000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000123".into()) => H520::from_low_u64_be(0x123)
     );
 
+    rpc_test! (
+      Eth:sign_transaction, TransactionRequest {
+        from: Address::from_low_u64_be(0x123), to: Some(Address::from_low_u64_be(0x123)),
+        gas: Some(0x5208.into()), gas_price: Some(0x1.into()),
+        value: Some(0x1.into()), data: None,
+        nonce: Some(0x0.into()), condition: None,
+        transaction_type: None, access_list: None,
+        max_fee_per_gas: None, max_priority_fee_per_gas: None,
+      }
+      =>
+      "eth_signTransaction", vec![r#"{"from":"0x0000000000000000000000000000000000000123","to":"0x0000000000000000000000000000000000000123","gas":"0x5208","gasPrice":"0x1","value":"0x1","nonce":"0x0"}"#];
+      ::serde_json::from_str(EXAMPLE_RAW_TX).unwrap()
+      => ::serde_json::from_str::<RawTransaction>(EXAMPLE_RAW_TX).unwrap()
+    );
+
     rpc_test! (
       Eth:submit_hashrate, 0x123, H256::from_low_u64_be(0x456)
       =>
@@ -934,4 +1091,29 @@ mod tests {
       ::serde_json::from_str(EXAMPLE_PROOF).unwrap()
       => Some(::serde_json::from_str::<Proof>(EXAMPLE_PROOF).unwrap())
     }
+
+    #[test]
+    fn only_calls_return_reverts() {
+        let mut transport = crate::transports::test::TestTransport::default();
+        transport.add_error_with_data(3, "execution reverted", Value::String("0x1234".into()));
+        transport.add_error_with_data(3, "execution reverted", Value::String("0x1234".into()));
+        let eth = Eth::new(&transport);
+
+        let call = futures::executor::block_on(eth.call(CallRequest::default(), None));
+        let send = futures::executor::block_on(eth.send_raw_transaction(vec![0x12].into()));
+
+        assert_eq!(
+            call,
+            Err(crate::Error::Revert(crate::error::Revert {
+                message: "execution reverted".into(),
+                data: vec![0x12, 0x34].into(),
+            }))
+        );
+        assert_eq!(
+            send,
+            Err(crate::Error::Rpc(
+                r#"RpcError { code: 3, message: "execution reverted" }"#.into()
+            ))
+        );
+    }
 }
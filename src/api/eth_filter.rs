@@ -4,23 +4,33 @@ use crate::prelude::*;
 use crate::{
     api::Namespace,
     error, helpers,
+    sleeper::{NoopSleeper, PollOptions, Sleeper},
     types::{Filter, Log, H256},
     Transport,
 };
-use futures::{stream, Stream, TryStreamExt};
-// use futures_timer::Delay;
 use core::{fmt, marker::PhantomData, time::Duration};
+use futures::{stream, Stream, TryStreamExt};
 use serde::de::DeserializeOwned;
 
-fn filter_stream<T: Transport, I: DeserializeOwned>(
+fn filter_stream<T: Transport, I: DeserializeOwned, S: Sleeper>(
     base: BaseFilter<T, I>,
-    poll_interval: Duration,
+    options: PollOptions<S>,
 ) -> impl Stream<Item = error::Result<I>> {
-    stream::unfold(base, move |base| async move {
-        // Delay::new(poll_interval).await;
-        let items = base.poll().await;
-        let items = items.map(Option::unwrap_or_default);
-        Some((items, base))
+    // The first poll is made right away, the sleeper is called before the following ones.
+    // The stream ends after yielding the error of the sleeper.
+    stream::unfold(Some((base, false)), move |state| {
+        let options = options.clone();
+        async move {
+            let (base, started) = state?;
+            if started {
+                if let Err(err) = options.sleep().await {
+                    return Some((Err(err), None));
+                }
+            }
+            let items = base.poll().await;
+            let items = items.map(Option::unwrap_or_default);
+            Some((items, Some((base, true))))
+        }
     })
     // map I to Result<I> even though it is always Ok so that try_flatten works
     .map_ok(|items| stream::iter(items.into_iter().map(Ok)))
@@ -129,8 +139,17 @@ impl<T: Transport, I: DeserializeOwned> BaseFilter<T, I> {
     }
 
     /// Returns the stream of items which automatically polls the server
+    ///
+    /// The server is polled again right away, `poll_interval` isn't waited; use
+    /// `stream_with_options` to wait between two polls.
     pub fn stream(self, poll_interval: Duration) -> impl Stream<Item = error::Result<I>> {
-        filter_stream(self, poll_interval)
+        self.stream_with_options(PollOptions::new(poll_interval).sleeper(NoopSleeper))
+    }
+
+    /// Returns the stream of items which automatically polls the server, waiting between two polls
+    /// as set by `options`.
+    pub fn stream_with_options<S: Sleeper>(self, options: PollOptions<S>) -> impl Stream<Item = error::Result<I>> {
+        filter_stream(self, options)
     }
 }
 
@@ -197,7 +216,9 @@ mod tests {
     use super::EthFilter;
     use crate::{
         api::Namespace,
+        error::Error,
         rpc::Value,
+        sleeper::{PollOptions, StepSleeper},
         transports::test::TestTransport,
         types::{Address, FilterBuilder, Log, H256},
     };
@@ -391,6 +412,33 @@ mod tests {
         transport.assert_request("eth_getFilterChanges", &[r#""0x123""#.into()]);
     }
 
+    #[test]
+    fn blocks_filter_stream_stops_with_sleeper() {
+        // given
+        let mut transport = TestTransport::default();
+        transport.set_response(Value::String("0x123".into()));
+        transport.add_response(Value::Array(vec![]));
+        transport.add_response(Value::Array(vec![Value::String(
+            r#"0x0000000000000000000000000000000000000000000000000000000000000456"#.into(),
+        )]));
+        let result: Vec<_> = {
+            let eth = EthFilter::new(&transport);
+
+            // when
+            let filter = futures::executor::block_on(eth.create_blocks_filter()).unwrap();
+            let stream =
+                filter.stream_with_options(PollOptions::new(Duration::from_secs(1)).sleeper(StepSleeper::new(1)));
+            futures::executor::block_on_stream(stream.boxed_local()).collect()
+        };
+
+        // then
+        assert_eq!(result, vec![Ok(H256::from_low_u64_be(0x456)), Err(Error::NotYet)]);
+        transport.assert_request("eth_newBlockFilter", &[]);
+        transport.assert_request("eth_getFilterChanges", &[r#""0x123""#.into()]);
+        transport.assert_request("eth_getFilterChanges", &[r#""0x123""#.into()]);
+        transport.assert_no_more_requests();
+    }
+
     #[test]
     fn pending_transactions_filter() {
         // given
@@ -0,0 +1,394 @@
+//! Subscriptions emulated by polling, for request/response transports.
+
+use crate::prelude::*;
+use crate::{
+    api::{Eth, Namespace},
+    error,
+    helpers::{self, CallFuture},
+    logs::{BlockRef, LogScanner, ScanCursor, ScanEvent},
+    sleeper::{PollOptions, Sleeper},
+    types::{BlockHeader, BlockNumber, Filter, Log},
+    Transport,
+};
+use alloc::collections::VecDeque;
+use futures::{stream, Stream};
+
+/// Emulates `eth_subscribe` on top of `eth_blockNumber`, `eth_getBlockByNumber` and `eth_getLogs`.
+///
+/// No node-side filter is installed. The node is polled right away, then the subscription waits
+/// between two polls as set by its [`PollOptions`], including after a failed poll. It ends after
+/// yielding the error of the sleeper, e.g. [`Error::NotYet`](crate::error::Error::NotYet) once a
+/// `StepSleeper` runs out.
+#[derive(Debug, Clone)]
+pub struct EthPollSubscribe<T> {
+    transport: T,
+}
+
+impl<T: Transport> Namespace<T> for EthPollSubscribe<T> {
+    fn new(transport: T) -> Self
+    where
+        Self: Sized,
+    {
+        EthPollSubscribe { transport }
+    }
+
+    fn transport(&self) -> &T {
+        &self.transport
+    }
+}
+
+struct HeadsState<T> {
+    transport: T,
+    /// Whether the node was polled already, so the next poll waits first.
+    polled: bool,
+    cursor: Option<ScanCursor>,
+    pending: VecDeque<BlockHeader>,
+}
+
+impl<T: Transport> HeadsState<T> {
+    async fn poll(&mut self) -> error::Result<()> {
+        let head = Eth::new(self.transport.clone()).block_number().await?.as_u64();
+        let cursor = match self.cursor {
+            Some(ref mut cursor) => cursor,
+            None => {
+                self.cursor = Some(ScanCursor::new(head + 1));
+                return Ok(());
+            }
+        };
+        while cursor.next_block <= head {
+            let number = cursor.next_block;
+            let block = BlockNumber::Number(number.into());
+            let block = helpers::serialize(&block);
+            let include_txs = helpers::serialize(&false);
+            let header: Option<BlockHeader> =
+                CallFuture::new(self.transport.execute("eth_getBlockByNumber", vec![block, include_txs])).await?;
+            let (header, hash) = match header {
+                Some(header) => match header.hash {
+                    Some(hash) => (header, hash),
+                    None => break,
+                },
+                None => break,
+            };
+            // on a reorg, walk back until the new chain joins the emitted one and emit its blocks again
+            if let Some(last) = cursor.recent.last().copied() {
+                if header.parent_hash != last.hash {
+                    cursor.recent.pop();
+                    cursor.next_block = last.number;
+                    continue;
+                }
+            }
+            cursor.record(BlockRef { number, hash });
+            cursor.trim();
+            cursor.next_block = number + 1;
+            self.pending.push_back(header);
+        }
+        Ok(())
+    }
+}
+
+struct LogsState<T: Transport> {
+    eth: Eth<T>,
+    scanner: LogScanner<T>,
+    /// Whether the node was polled already, so the next poll waits first.
+    polled: bool,
+    cursor: Option<ScanCursor>,
+    pending: VecDeque<Log>,
+    /// Logs yielded from the blocks the cursor can still roll back.
+    emitted: VecDeque<Log>,
+}
+
+impl<T: Transport> LogsState<T> {
+    async fn poll(&mut self) -> error::Result<()> {
+        let cursor = match self.cursor {
+            Some(ref mut cursor) => cursor,
+            None => {
+                let head = self.eth.block_number().await?.as_u64();
+                self.cursor = Some(ScanCursor::new(head + 1));
+                return Ok(());
+            }
+        };
+        for event in self.scanner.scan(cursor).await? {
+            match event {
+                ScanEvent::Log(log) => {
+                    self.emitted.push_back(log.clone());
+                    self.pending.push_back(log);
+                }
+                ScanEvent::Removed(log) => self.pending.push_back(log),
+                ScanEvent::Rollback { after_block } => {
+                    let (kept, removed) = self
+                        .emitted
+                        .drain(..)
+                        .partition(|log| log.block_number <= Some(after_block.into()));
+                    self.emitted = kept;
+                    self.pending.extend(removed.into_iter().map(|log: Log| Log {
+                        removed: Some(true),
+                        ..log
+                    }));
+                }
+            }
+        }
+        let oldest = cursor.recent.first().map_or(cursor.next_block, |block| block.number);
+        while matches!(self.emitted.front(), Some(log) if log.block_number < Some(oldest.into())) {
+            self.emitted.pop_front();
+        }
+        Ok(())
+    }
+}
+
+impl<T: Transport> EthPollSubscribe<T> {
+    /// Create a new heads subscription.
+    ///
+    /// Yields the header of every block mined after the subscription was created, in order. After
+    /// a reorg, the headers of the new canonical blocks are yielded again from the fork point.
+    pub fn subscribe_new_heads<S: Sleeper>(
+        &self,
+        poll: PollOptions<S>,
+    ) -> impl Stream<Item = error::Result<BlockHeader>> {
+        let state = HeadsState {
+            transport: self.transport.clone(),
+            polled: false,
+            cursor: None,
+            pending: VecDeque::new(),
+        };
+        stream::unfold(Some(state), move |state| {
+            let poll = poll.clone();
+            async move {
+                let mut state = state?;
+                loop {
+                    if let Some(header) = state.pending.pop_front() {
+                        return Some((Ok(header), Some(state)));
+                    }
+                    if state.polled {
+                        if let Err(err) = poll.sleep().await {
+                            return Some((Err(err), None));
+                        }
+                    }
+                    state.polled = true;
+                    if let Err(err) = state.poll().await {
+                        return Some((Err(err), Some(state)));
+                    }
+                }
+            }
+        })
+    }
+
+    /// Create a logs subscription.
+    ///
+    /// Yields the logs matching `filter` in blocks mined after the subscription was created.
+    /// The block range of the filter is ignored. Reorgs are followed with a [`LogScanner`]: the
+    /// logs of orphaned blocks are yielded again with `removed` set, like `eth_subscribe` does.
+    pub fn subscribe_logs<S: Sleeper>(
+        &self,
+        filter: Filter,
+        poll: PollOptions<S>,
+    ) -> impl Stream<Item = error::Result<Log>> {
+        let eth = Eth::new(self.transport.clone());
+        let state = LogsState {
+            scanner: LogScanner::new(eth.clone(), filter),
+            eth,
+            polled: false,
+            cursor: None,
+            pending: VecDeque::new(),
+            emitted: VecDeque::new(),
+        };
+        stream::unfold(Some(state), move |state| {
+            let poll = poll.clone();
+            async move {
+                let mut state = state?;
+                loop {
+                    if let Some(log) = state.pending.pop_front() {
+                        return Some((Ok(log), Some(state)));
+                    }
+                    if state.polled {
+                        if let Err(err) = poll.sleep().await {
+                            return Some((Err(err), None));
+                        }
+                    }
+                    state.polled = true;
+                    if let Err(err) = state.poll().await {
+                        return Some((Err(err), Some(state)));
+                    }
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EthPollSubscribe;
+    use crate::{
+        api::Namespace,
+        error::Error,
+        rpc::Value,
+        sleeper::{NoopSleeper, PollOptions, Sleeper, StepSleeper},
+        transports::test::{block_response, TestTransport},
+        types::{Filter, H256},
+    };
+    use core::time::Duration;
+    use futures::{executor::block_on, StreamExt};
+
+    fn canonical(number: u64) -> Value {
+        block_response(number, H256::from_low_u64_be(number), H256::from_low_u64_be(number - 1))
+    }
+
+    fn fork(number: u64) -> H256 {
+        H256::from_low_u64_be(0x100 + number)
+    }
+
+    fn poll<S: Sleeper>(sleeper: S) -> PollOptions<S> {
+        PollOptions::new(Duration::from_secs(1)).sleeper(sleeper)
+    }
+
+    fn block_request(number: &str) -> [String; 2] {
+        [format!(r#""{}""#, number), "false".into()]
+    }
+
+    #[test]
+    fn polls_new_heads() {
+        let mut transport = TestTransport::default();
+        transport.add_response(Value::String("0x1".into()));
+        transport.add_response(Value::String("0x3".into()));
+        transport.add_response(canonical(2));
+        transport.add_response(canonical(3));
+        let subscribe = EthPollSubscribe::new(transport.clone());
+
+        let heads = subscribe.subscribe_new_heads(poll(NoopSleeper));
+        let heads = block_on(heads.take(2).collect::<Vec<_>>());
+
+        assert_eq!(
+            heads.into_iter().map(|head| head.unwrap().number).collect::<Vec<_>>(),
+            vec![Some(2.into()), Some(3.into())]
+        );
+        transport.assert_request("eth_blockNumber", &[]);
+        transport.assert_request("eth_blockNumber", &[]);
+        transport.assert_request("eth_getBlockByNumber", &block_request("0x2"));
+        transport.assert_request("eth_getBlockByNumber", &block_request("0x3"));
+        transport.assert_no_more_requests();
+    }
+
+    #[test]
+    fn new_heads_follow_reorgs() {
+        let mut transport = TestTransport::default();
+        transport.add_response(Value::String("0x1".into()));
+        transport.add_response(Value::String("0x2".into()));
+        transport.add_response(canonical(2));
+        transport.add_response(Value::String("0x3".into()));
+        transport.add_response(block_response(3, fork(3), fork(2)));
+        transport.add_response(block_response(2, fork(2), H256::from_low_u64_be(1)));
+        transport.add_response(block_response(3, fork(3), fork(2)));
+        let subscribe = EthPollSubscribe::new(transport.clone());
+
+        let heads = subscribe.subscribe_new_heads(poll(NoopSleeper));
+        let heads = block_on(heads.take(3).collect::<Vec<_>>());
+
+        assert_eq!(
+            heads.into_iter().map(|head| head.unwrap().hash).collect::<Vec<_>>(),
+            vec![Some(H256::from_low_u64_be(2)), Some(fork(2)), Some(fork(3))]
+        );
+        transport.assert_request("eth_blockNumber", &[]);
+        transport.assert_request("eth_blockNumber", &[]);
+        transport.assert_request("eth_getBlockByNumber", &block_request("0x2"));
+        transport.assert_request("eth_blockNumber", &[]);
+        transport.assert_request("eth_getBlockByNumber", &block_request("0x3"));
+        transport.assert_request("eth_getBlockByNumber", &block_request("0x2"));
+        transport.assert_request("eth_getBlockByNumber", &block_request("0x3"));
+        transport.assert_no_more_requests();
+    }
+
+    #[test]
+    fn polls_logs() {
+        let mut transport = TestTransport::default();
+        transport.add_response(Value::String("0x1".into()));
+        transport.add_response(Value::String("0x2".into()));
+        transport.add_response(canonical(2));
+        transport.add_response(serde_json::json!([{
+            "address": "0x0000000000000000000000000000000000000123",
+            "topics": [],
+            "data": "0x",
+            "blockNumber": "0x2",
+            "blockHash": H256::from_low_u64_be(2),
+        }]));
+        let subscribe = EthPollSubscribe::new(transport.clone());
+
+        let logs = subscribe.subscribe_logs(Filter::default(), poll(NoopSleeper));
+        let logs = block_on(logs.take(1).collect::<Vec<_>>());
+
+        assert_eq!(logs[0].as_ref().unwrap().block_number, Some(2.into()));
+        transport.assert_request("eth_blockNumber", &[]);
+        transport.assert_request("eth_blockNumber", &[]);
+        transport.assert_request("eth_getBlockByNumber", &block_request("0x2"));
+        transport.assert_request("eth_getLogs", &[r#"{"fromBlock":"0x2","toBlock":"0x2"}"#.into()]);
+        transport.assert_no_more_requests();
+    }
+
+    #[test]
+    fn removes_logs_of_orphaned_blocks() {
+        let mut transport = TestTransport::default();
+        transport.add_response(Value::String("0x1".into()));
+        transport.add_response(Value::String("0x2".into()));
+        transport.add_response(canonical(2));
+        transport.add_response(serde_json::json!([{
+            "address": "0x0000000000000000000000000000000000000123",
+            "topics": [],
+            "data": "0x",
+            "blockNumber": "0x2",
+            "blockHash": H256::from_low_u64_be(2),
+        }]));
+        transport.add_response(Value::String("0x3".into()));
+        transport.add_response(block_response(3, fork(3), fork(2)));
+        transport.add_response(block_response(2, fork(2), H256::from_low_u64_be(1)));
+        transport.add_response(block_response(3, fork(3), fork(2)));
+        transport.add_response(Value::Array(vec![]));
+        let subscribe = EthPollSubscribe::new(transport.clone());
+
+        let logs = subscribe.subscribe_logs(Filter::default(), poll(StepSleeper::new(2)));
+        let logs = block_on(logs.collect::<Vec<_>>());
+
+        assert_eq!(logs.len(), 3);
+        let (mined, removed) = (logs[0].as_ref().unwrap(), logs[1].as_ref().unwrap());
+        assert!(!mined.is_removed());
+        assert!(removed.is_removed());
+        assert_eq!(removed.block_hash, Some(H256::from_low_u64_be(2)));
+        assert_eq!(logs[2], Err(Error::NotYet));
+        transport.assert_request("eth_blockNumber", &[]);
+        transport.assert_request("eth_blockNumber", &[]);
+        transport.assert_request("eth_getBlockByNumber", &block_request("0x2"));
+        transport.assert_request("eth_getLogs", &[r#"{"fromBlock":"0x2","toBlock":"0x2"}"#.into()]);
+        transport.assert_request("eth_blockNumber", &[]);
+        transport.assert_request("eth_getBlockByNumber", &block_request("0x3"));
+        transport.assert_request("eth_getBlockByNumber", &block_request("0x2"));
+        transport.assert_request("eth_getBlockByNumber", &block_request("0x3"));
+        transport.assert_request("eth_getLogs", &[r#"{"fromBlock":"0x2","toBlock":"0x3"}"#.into()]);
+        transport.assert_no_more_requests();
+    }
+
+    #[test]
+    fn waits_before_retrying_failed_poll() {
+        let mut transport = TestTransport::default();
+        transport.add_error(-32000, "header not found");
+        let subscribe = EthPollSubscribe::new(transport.clone());
+
+        let logs = subscribe.subscribe_logs(Filter::default(), poll(StepSleeper::new(0)));
+        let logs = block_on(logs.collect::<Vec<_>>());
+        assert_eq!(logs.len(), 2);
+        assert!(matches!(logs[0], Err(Error::Rpc(_))));
+        assert_eq!(logs[1], Err(Error::NotYet));
+        transport.assert_request("eth_blockNumber", &[]);
+        transport.assert_no_more_requests();
+    }
+
+    #[test]
+    fn ends_when_sleeper_gives_up() {
+        let mut transport = TestTransport::default();
+        transport.add_response(Value::String("0x1".into()));
+        let subscribe = EthPollSubscribe::new(transport.clone());
+
+        let heads = subscribe.subscribe_new_heads(poll(StepSleeper::new(0)));
+        let heads = block_on(heads.collect::<Vec<_>>());
+        assert_eq!(heads.len(), 1);
+        assert!(matches!(heads[0], Err(Error::NotYet)));
+        transport.assert_request("eth_blockNumber", &[]);
+        transport.assert_no_more_requests();
+    }
+}
@@ -1,9 +1,12 @@
 //! `Web3` implementation
 
 mod accounts;
+mod dev;
 mod eth;
 mod eth_filter;
+mod eth_poll_subscribe;
 mod net;
+mod ots;
 mod parity;
 mod parity_accounts;
 mod parity_set;
@@ -14,9 +17,12 @@ mod web3;
 
 pub use self::{
     accounts::Accounts,
+    dev::{Dev, DevFlavor, Forking},
     eth::Eth,
     eth_filter::{BaseFilter, EthFilter},
+    eth_poll_subscribe::EthPollSubscribe,
     net::Net,
+    ots::Ots,
     parity::Parity,
     parity_accounts::ParityAccounts,
     parity_set::ParitySet,
@@ -27,12 +33,14 @@ pub use self::{
 };
 
 use crate::{
-    confirm, error,
+    confirm::{self, ConfirmationOptions},
+    error,
+    sleeper::Sleeper,
     types::{Bytes, TransactionReceipt, TransactionRequest, U64},
     Transport,
 };
-use futures::Future;
 use core::time::Duration;
+use futures::Future;
 
 /// Common API for all namespaces
 pub trait Namespace<T: Transport>: Clone {
@@ -90,6 +98,11 @@ impl<T: Transport> Web3<T> {
         self.api()
     }
 
+    /// Access subscriptions emulated by polling
+    pub fn eth_poll_subscribe(&self) -> eth_poll_subscribe::EthPollSubscribe<T> {
+        self.api()
+    }
+
     /// Access methods from `parity` namespace
     pub fn parity(&self) -> parity::Parity<T> {
         self.api()
@@ -144,6 +157,15 @@ impl<T: Transport> Web3<T> {
         confirm::send_transaction_with_confirmation(self.transport.clone(), tx, poll_interval, confirmations).await
     }
 
+    /// Sends transaction and returns future resolved after transaction is confirmed as set by `options`
+    pub async fn send_transaction_with_options<S: Sleeper>(
+        &self,
+        tx: TransactionRequest,
+        options: ConfirmationOptions<S>,
+    ) -> error::Result<TransactionReceipt> {
+        confirm::send_transaction_with_options(self.transport.clone(), tx, options).await
+    }
+
     /// Sends raw transaction and returns future resolved after transaction is confirmed
     pub async fn send_raw_transaction_with_confirmation(
         &self,
@@ -153,4 +175,13 @@ impl<T: Transport> Web3<T> {
     ) -> error::Result<TransactionReceipt> {
         confirm::send_raw_transaction_with_confirmation(self.transport.clone(), tx, poll_interval, confirmations).await
     }
+
+    /// Sends raw transaction and returns future resolved after transaction is confirmed as set by `options`
+    pub async fn send_raw_transaction_with_options<S: Sleeper>(
+        &self,
+        tx: Bytes,
+        options: ConfirmationOptions<S>,
+    ) -> error::Result<TransactionReceipt> {
+        confirm::send_raw_transaction_with_options(self.transport.clone(), tx, options).await
+    }
 }
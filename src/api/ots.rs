@@ -0,0 +1,216 @@
+//! `Ots` namespace (Otterscan API, served by Erigon and reth)
+
+use crate::prelude::*;
+use crate::{
+    api::Namespace,
+    helpers::{self, CallFuture},
+    types::{Address, BlockId, ContractCreator, InternalOperation, OtsTrace, OtsTransactionsPage, H256},
+    Transport,
+};
+
+/// `Ots` namespace
+#[derive(Debug, Clone)]
+pub struct Ots<T> {
+    transport: T,
+}
+
+impl<T: Transport> Namespace<T> for Ots<T> {
+    fn new(transport: T) -> Self
+    where
+        Self: Sized,
+    {
+        Ots { transport }
+    }
+
+    fn transport(&self) -> &T {
+        &self.transport
+    }
+}
+
+impl<T: Transport> Ots<T> {
+    /// Get the version of the Otterscan API supported by the node.
+    pub fn api_level(&self) -> CallFuture<u64, T::Out> {
+        CallFuture::new(self.transport.execute("ots_getApiLevel", vec![]))
+    }
+
+    /// Check whether the given address holds code at the given block.
+    pub fn has_code(&self, address: Address, block: BlockId) -> CallFuture<bool, T::Out> {
+        let address = helpers::serialize(&address);
+        let block = helpers::serialize(&block);
+        CallFuture::new(self.transport.execute("ots_hasCode", vec![address, block]))
+    }
+
+    /// Get the transaction and address that created the given contract.
+    ///
+    /// Returns `None` if the address is not a contract.
+    pub fn contract_creator(&self, address: Address) -> CallFuture<Option<ContractCreator>, T::Out> {
+        let address = helpers::serialize(&address);
+        CallFuture::new(self.transport.execute("ots_getContractCreator", vec![address]))
+    }
+
+    /// Get the hash of the transaction sent by `sender` with the given nonce.
+    ///
+    /// Returns `None` if no such transaction was mined.
+    pub fn transaction_by_sender_and_nonce(&self, sender: Address, nonce: u64) -> CallFuture<Option<H256>, T::Out> {
+        let sender = helpers::serialize(&sender);
+        let nonce = helpers::serialize(&nonce);
+        CallFuture::new(
+            self.transport
+                .execute("ots_getTransactionBySenderAndNonce", vec![sender, nonce]),
+        )
+    }
+
+    /// Search transactions touching `address` in blocks before `block` (exclusive), newest first.
+    ///
+    /// Pass `0` as `block` to start from the latest block.
+    pub fn search_transactions_before(
+        &self,
+        address: Address,
+        block: u64,
+        page_size: u64,
+    ) -> CallFuture<OtsTransactionsPage, T::Out> {
+        let address = helpers::serialize(&address);
+        let block = helpers::serialize(&block);
+        let page_size = helpers::serialize(&page_size);
+        CallFuture::new(
+            self.transport
+                .execute("ots_searchTransactionsBefore", vec![address, block, page_size]),
+        )
+    }
+
+    /// Search transactions touching `address` in blocks after `block` (exclusive), newest first.
+    ///
+    /// Pass `0` as `block` to start from the genesis block.
+    pub fn search_transactions_after(
+        &self,
+        address: Address,
+        block: u64,
+        page_size: u64,
+    ) -> CallFuture<OtsTransactionsPage, T::Out> {
+        let address = helpers::serialize(&address);
+        let block = helpers::serialize(&block);
+        let page_size = helpers::serialize(&page_size);
+        CallFuture::new(
+            self.transport
+                .execute("ots_searchTransactionsAfter", vec![address, block, page_size]),
+        )
+    }
+
+    /// Get the call tree of a transaction, without the full VM trace.
+    pub fn trace_transaction(&self, hash: H256) -> CallFuture<Vec<OtsTrace>, T::Out> {
+        let hash = helpers::serialize(&hash);
+        CallFuture::new(self.transport.execute("ots_traceTransaction", vec![hash]))
+    }
+
+    /// Get value transfers and contract creations made from inside a transaction.
+    pub fn internal_operations(&self, hash: H256) -> CallFuture<Vec<InternalOperation>, T::Out> {
+        let hash = helpers::serialize(&hash);
+        CallFuture::new(self.transport.execute("ots_getInternalOperations", vec![hash]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Ots;
+    use crate::{
+        api::Namespace,
+        rpc::Value,
+        types::{Address, BlockNumber, ContractCreator, InternalOperation, OtsTransactionsPage, H256},
+    };
+
+    const EXAMPLE_PAGE: &str = r#"{
+    "txs": [{
+      "hash": "0xc6ef2fc5426d6ad6fd9e2a26abeab0aa2411b7ab17f30a99d3cb96aed1d1055b",
+      "nonce": "0x0",
+      "blockHash": "0xbeab0aa2411b7ab17f30a99d3cb9c6ef2fc5426d6ad6fd9e2a26a6aed1d1055b",
+      "blockNumber": "0x15df",
+      "transactionIndex": "0x1",
+      "from": "0x407d73d8a49eeb85d32cf465507dd71d507100c1",
+      "to": "0x85dd43d8a49eeb85d32cf465507dd71d507100c1",
+      "value": "0x7f110",
+      "gas": "0x7f110",
+      "gasPrice": "0x09184e72a000",
+      "input": "0x"
+    }],
+    "receipts": [{
+      "transactionHash": "0xc6ef2fc5426d6ad6fd9e2a26abeab0aa2411b7ab17f30a99d3cb96aed1d1055b",
+      "blockHash": "0xbeab0aa2411b7ab17f30a99d3cb9c6ef2fc5426d6ad6fd9e2a26a6aed1d1055b",
+      "blockNumber": "0x15df",
+      "from": "0x407d73d8a49eeb85d32cf465507dd71d507100c1",
+      "to": "0x85dd43d8a49eeb85d32cf465507dd71d507100c1",
+      "gasUsed": "0x5208",
+      "contractAddress": null,
+      "logs": null,
+      "logsBloom": null,
+      "status": "0x1",
+      "effectiveGasPrice": "0x09184e72a000",
+      "timestamp": "0x54e34e8e"
+    }],
+    "firstPage": true,
+    "lastPage": false
+  }"#;
+
+    rpc_test! (
+      Ots:api_level => "ots_getApiLevel";
+      Value::Number(8.into()) => 8u64
+    );
+
+    rpc_test! (
+      Ots:has_code, Address::from_low_u64_be(0x123), BlockNumber::Latest
+      =>
+      "ots_hasCode", vec![r#""0x0000000000000000000000000000000000000123""#, r#""latest""#];
+      Value::Bool(true) => true
+    );
+
+    rpc_test! (
+      Ots:contract_creator, Address::from_low_u64_be(0x123)
+      =>
+      "ots_getContractCreator", vec![r#""0x0000000000000000000000000000000000000123""#];
+      serde_json::json!({
+        "hash": "0x0000000000000000000000000000000000000000000000000000000000000456",
+        "creator": "0x0000000000000000000000000000000000000789"
+      }) => Some(ContractCreator {
+        hash: H256::from_low_u64_be(0x456),
+        creator: Address::from_low_u64_be(0x789),
+      })
+    );
+
+    rpc_test! (
+      Ots:transaction_by_sender_and_nonce, Address::from_low_u64_be(0x123), 5u64
+      =>
+      "ots_getTransactionBySenderAndNonce", vec![r#""0x0000000000000000000000000000000000000123""#, "5"];
+      Value::String("0x0000000000000000000000000000000000000000000000000000000000000456".into())
+      => Some(H256::from_low_u64_be(0x456))
+    );
+
+    rpc_test! (
+      Ots:transaction_by_sender_and_nonce:transaction_by_sender_and_nonce_not_found,
+      Address::from_low_u64_be(0x123), 5u64
+      =>
+      "ots_getTransactionBySenderAndNonce", vec![r#""0x0000000000000000000000000000000000000123""#, "5"];
+      Value::Null => None
+    );
+
+    rpc_test! (
+      Ots:search_transactions_before, Address::from_low_u64_be(0x123), 0u64, 25u64
+      =>
+      "ots_searchTransactionsBefore", vec![r#""0x0000000000000000000000000000000000000123""#, "0", "25"];
+      ::serde_json::from_str(EXAMPLE_PAGE).unwrap()
+      => ::serde_json::from_str::<OtsTransactionsPage>(EXAMPLE_PAGE).unwrap()
+    );
+
+    rpc_test! (
+      Ots:search_transactions_after, Address::from_low_u64_be(0x123), 100u64, 25u64
+      =>
+      "ots_searchTransactionsAfter", vec![r#""0x0000000000000000000000000000000000000123""#, "100", "25"];
+      ::serde_json::from_str(EXAMPLE_PAGE).unwrap()
+      => ::serde_json::from_str::<OtsTransactionsPage>(EXAMPLE_PAGE).unwrap()
+    );
+
+    rpc_test! (
+      Ots:internal_operations, H256::from_low_u64_be(0x123)
+      =>
+      "ots_getInternalOperations", vec![r#""0x0000000000000000000000000000000000000000000000000000000000000123""#];
+      Value::Array(vec![]) => Vec::<InternalOperation>::new()
+    );
+}
@@ -0,0 +1,69 @@
+//! Cryptographic primitives of the signature backend.
+//!
+//! Inside contracts, `ink_env` is used. Elsewhere, the pure-Rust `k256` backend is used when
+//! enabled, which is always the case in tests.
+
+pub(crate) use imp::*;
+
+#[cfg(all(any(feature = "k256", test), not(all(feature = "ink_env", target_arch = "wasm32"))))]
+mod imp {
+    use crate::{
+        signing::{keccak256, RecoveryError},
+        types::Address,
+    };
+    use k256::{
+        ecdsa::{RecoveryId, Signature, VerifyingKey},
+        sha2::{Digest, Sha256},
+    };
+
+    pub fn recover_address(
+        prehash: &[u8; 32],
+        signature: &[u8; 64],
+        recovery_id: u8,
+    ) -> Result<Address, RecoveryError> {
+        let invalid = |_| RecoveryError::InvalidSignature;
+        let mut signature = Signature::from_slice(signature).map_err(invalid)?;
+        let mut recovery_id = RecoveryId::from_byte(recovery_id).ok_or(RecoveryError::InvalidSignature)?;
+        // k256 only accepts low `s`, whose negation flips the parity of the signature's point
+        if let Some(normalized) = signature.normalize_s() {
+            signature = normalized;
+            recovery_id = RecoveryId::new(!recovery_id.is_y_odd(), recovery_id.is_x_reduced());
+        }
+
+        let key = VerifyingKey::recover_from_prehash(prehash, &signature, recovery_id).map_err(invalid)?;
+        let public = key.to_encoded_point(false);
+        Ok(Address::from_slice(&keccak256(&public.as_bytes()[1..])[12..]))
+    }
+
+    pub fn sha256(data: &[u8]) -> [u8; 32] {
+        Sha256::digest(data).into()
+    }
+}
+
+#[cfg(all(feature = "ink_env", any(target_arch = "wasm32", not(any(feature = "k256", test)))))]
+mod imp {
+    use crate::{signing::RecoveryError, types::Address};
+
+    pub fn recover_address(
+        prehash: &[u8; 32],
+        signature: &[u8; 64],
+        recovery_id: u8,
+    ) -> Result<Address, RecoveryError> {
+        let mut recoverable_signature = [0; 65];
+        recoverable_signature[..64].copy_from_slice(signature);
+        recoverable_signature[64] = recovery_id;
+
+        let mut public = [0; 33];
+        ink_env::ecdsa_recover(&recoverable_signature, prehash, &mut public)
+            .map_err(|_| RecoveryError::InvalidSignature)?;
+        let mut address = [0; 20];
+        ink_env::ecdsa_to_eth_address(&public, &mut address).map_err(|_| RecoveryError::InvalidSignature)?;
+        Ok(address.into())
+    }
+
+    pub fn sha256(data: &[u8]) -> [u8; 32] {
+        let mut hash = [0; 32];
+        ink_env::hash_bytes::<ink_env::hash::Sha2x256>(data, &mut hash);
+        hash
+    }
+}
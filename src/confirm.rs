@@ -3,11 +3,67 @@
 use crate::{
     api::{Eth, EthFilter, Namespace},
     error,
-    types::{Bytes, TransactionReceipt, TransactionRequest, H256, U64},
+    sleeper::{DefaultSleeper, NoopSleeper, PollOptions, Sleeper},
+    types::{BlockId, BlockNumber, Bytes, TransactionReceipt, TransactionRequest, H256, U64},
     Transport,
 };
-use futures::{Future, StreamExt};
 use core::time::Duration;
+use futures::{Future, StreamExt};
+
+/// How confirmations of a sent transaction are awaited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConfirmationMode {
+    /// Count new blocks with an `eth_newBlockFilter` filter.
+    #[default]
+    Filter,
+    /// Poll `eth_blockNumber` and `eth_getTransactionReceipt` only, for providers without filter support.
+    Polling,
+}
+
+/// How long and how confirmations of a sent transaction are awaited.
+#[derive(Debug, Clone)]
+pub struct ConfirmationOptions<S = DefaultSleeper> {
+    /// Number of blocks mined on top of the transaction's block.
+    pub confirmations: usize,
+    /// Wait between two polls.
+    pub poll: PollOptions<S>,
+    /// How the confirmations are counted.
+    pub mode: ConfirmationMode,
+}
+
+impl ConfirmationOptions {
+    /// Waits for `confirmations` blocks with a filter, polling every second with the [`DefaultSleeper`].
+    pub fn new(confirmations: usize) -> Self {
+        ConfirmationOptions {
+            confirmations,
+            poll: PollOptions::new(Duration::from_secs(1)),
+            mode: ConfirmationMode::default(),
+        }
+    }
+}
+
+impl<S: Sleeper> ConfirmationOptions<S> {
+    /// Sets the time to wait between two polls.
+    pub fn poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll.interval = poll_interval;
+        self
+    }
+
+    /// Sets the sleeper waiting between two polls.
+    pub fn sleeper<S2: Sleeper>(self, sleeper: S2) -> ConfirmationOptions<S2> {
+        ConfirmationOptions {
+            confirmations: self.confirmations,
+            poll: self.poll.sleeper(sleeper),
+            mode: self.mode,
+        }
+    }
+
+    /// Sets how the confirmations are counted.
+    pub fn mode(mut self, mode: ConfirmationMode) -> Self {
+        self.mode = mode;
+        self
+    }
+}
 
 /// Checks whether an event has been confirmed.
 pub trait ConfirmationCheck {
@@ -31,6 +87,9 @@ where
 }
 
 /// Should be used to wait for confirmations
+///
+/// The filter is polled again right away, `poll_interval` isn't waited; use
+/// [`wait_for_receipt_confirmations`] to wait between two polls.
 pub async fn wait_for_confirmations<T, V, F>(
     eth: Eth<T>,
     eth_filter: EthFilter<T>,
@@ -42,6 +101,23 @@ where
     T: Transport,
     V: ConfirmationCheck<Check = F>,
     F: Future<Output = error::Result<Option<U64>>>,
+{
+    let poll = PollOptions::new(poll_interval).sleeper(NoopSleeper);
+    wait_for_filter_confirmations(eth, eth_filter, poll, confirmations, check).await
+}
+
+async fn wait_for_filter_confirmations<T, V, F, S>(
+    eth: Eth<T>,
+    eth_filter: EthFilter<T>,
+    poll: PollOptions<S>,
+    confirmations: usize,
+    check: V,
+) -> error::Result<()>
+where
+    T: Transport,
+    V: ConfirmationCheck<Check = F>,
+    F: Future<Output = error::Result<Option<U64>>>,
+    S: Sleeper,
 {
     let filter = eth_filter.create_blocks_filter().await?;
     // TODO #396: The stream should have additional checks.
@@ -50,10 +126,15 @@ where
     //   erroring when it does complete.
     // * We do not handle the case where the stream returns an error which means we are wrongly counting it as a
     //   confirmation.
-    let filter_stream = filter.stream(poll_interval).skip(confirmations);
+    let filter_stream = filter.stream_with_options(poll).skip(confirmations);
     futures::pin_mut!(filter_stream);
     loop {
-        let _ = filter_stream.next().await;
+        match filter_stream.next().await {
+            Some(Err(error::Error::NotYet)) => return Err(error::Error::NotYet),
+            // The stream only ends once the sleeper gave up.
+            None => return Err(error::Error::NotYet),
+            _ => {}
+        }
         if let Some(confirmation_block_number) = check.check().await? {
             let block_number = eth.block_number().await?;
             if confirmation_block_number.low_u64() + confirmations as u64 <= block_number.low_u64() {
@@ -68,28 +149,68 @@ async fn transaction_receipt_block_number_check<T: Transport>(eth: &Eth<T>, hash
     Ok(receipt.and_then(|receipt| receipt.block_number))
 }
 
-async fn send_transaction_with_confirmation_<T: Transport>(
+/// Polls the receipt until enough blocks were mined on top of it, without using filters.
+///
+/// Once enough blocks were mined, the block hash of the receipt is checked against the canonical
+/// chain, so a receipt from a reorged block is never returned.
+async fn poll_receipt_confirmations<T: Transport, S: Sleeper>(
+    eth: &Eth<T>,
     hash: H256,
-    transport: T,
-    poll_interval: Duration,
+    poll: &PollOptions<S>,
     confirmations: usize,
 ) -> error::Result<TransactionReceipt> {
+    loop {
+        if let Some(receipt) = eth.transaction_receipt(hash).await? {
+            if let (Some(number), Some(block_hash)) = (receipt.block_number, receipt.block_hash) {
+                let block_number = eth.block_number().await?;
+                if number.low_u64() + confirmations as u64 <= block_number.low_u64() {
+                    let block = eth.block(BlockId::Number(BlockNumber::Number(number))).await?;
+                    if block.and_then(|block| block.hash) == Some(block_hash) {
+                        return Ok(receipt);
+                    }
+                }
+            }
+        }
+        poll.sleep().await?;
+    }
+}
+
+/// Waits until the transaction `hash` is confirmed as set by `options`, and returns its receipt.
+///
+/// Fails with the sleeper error if it gives up.
+pub async fn wait_for_receipt_confirmations<T, S>(
+    transport: T,
+    hash: H256,
+    options: ConfirmationOptions<S>,
+) -> error::Result<TransactionReceipt>
+where
+    T: Transport,
+    S: Sleeper,
+{
     let eth = Eth::new(transport.clone());
+    let ConfirmationOptions {
+        confirmations,
+        poll,
+        mode,
+    } = options;
+    if mode == ConfirmationMode::Polling {
+        return poll_receipt_confirmations(&eth, hash, &poll, confirmations).await;
+    }
     if confirmations > 0 {
         let confirmation_check = || transaction_receipt_block_number_check(&eth, hash);
         let eth_filter = EthFilter::new(transport.clone());
         let eth = eth.clone();
-        wait_for_confirmations(eth, eth_filter, poll_interval, confirmations, confirmation_check).await?;
+        wait_for_filter_confirmations(eth, eth_filter, poll, confirmations, confirmation_check).await?;
     }
-    // TODO #397: We should remove this `expect`. No matter what happens inside the node, this shouldn't be a panic.
-    let receipt = eth
-        .transaction_receipt(hash)
+    eth.transaction_receipt(hash)
         .await?
-        .expect("receipt can't be null after wait for confirmations; qed");
-    Ok(receipt)
+        .ok_or_else(|| error::Error::InvalidResponse("no receipt after waiting for confirmations".into()))
 }
 
 /// Sends transaction and returns future resolved after transaction is confirmed
+///
+/// Confirmations are polled without waiting `poll_interval`; use [`send_transaction_with_options`]
+/// to wait between two polls.
 pub async fn send_transaction_with_confirmation<T>(
     transport: T,
     tx: TransactionRequest,
@@ -98,12 +219,31 @@ pub async fn send_transaction_with_confirmation<T>(
 ) -> error::Result<TransactionReceipt>
 where
     T: Transport,
+{
+    let options = ConfirmationOptions::new(confirmations)
+        .poll_interval(poll_interval)
+        .sleeper(NoopSleeper);
+    send_transaction_with_options(transport, tx, options).await
+}
+
+/// Sends transaction and returns future resolved after transaction is confirmed as set by `options`
+pub async fn send_transaction_with_options<T, S>(
+    transport: T,
+    tx: TransactionRequest,
+    options: ConfirmationOptions<S>,
+) -> error::Result<TransactionReceipt>
+where
+    T: Transport,
+    S: Sleeper,
 {
     let hash = Eth::new(&transport).send_transaction(tx).await?;
-    send_transaction_with_confirmation_(hash, transport, poll_interval, confirmations).await
+    wait_for_receipt_confirmations(transport, hash, options).await
 }
 
 /// Sends raw transaction and returns future resolved after transaction is confirmed
+///
+/// Confirmations are polled without waiting `poll_interval`; use
+/// [`send_raw_transaction_with_options`] to wait between two polls.
 pub async fn send_raw_transaction_with_confirmation<T>(
     transport: T,
     tx: Bytes,
@@ -112,17 +252,37 @@ pub async fn send_raw_transaction_with_confirmation<T>(
 ) -> error::Result<TransactionReceipt>
 where
     T: Transport,
+{
+    let options = ConfirmationOptions::new(confirmations)
+        .poll_interval(poll_interval)
+        .sleeper(NoopSleeper);
+    send_raw_transaction_with_options(transport, tx, options).await
+}
+
+/// Sends raw transaction and returns future resolved after transaction is confirmed as set by `options`
+pub async fn send_raw_transaction_with_options<T, S>(
+    transport: T,
+    tx: Bytes,
+    options: ConfirmationOptions<S>,
+) -> error::Result<TransactionReceipt>
+where
+    T: Transport,
+    S: Sleeper,
 {
     let hash = Eth::new(&transport).send_raw_transaction(tx).await?;
-    send_transaction_with_confirmation_(hash, transport, poll_interval, confirmations).await
+    wait_for_receipt_confirmations(transport, hash, options).await
 }
 
 #[cfg(test)]
 mod tests {
-    use super::send_transaction_with_confirmation;
+    use super::{
+        send_transaction_with_confirmation, send_transaction_with_options, ConfirmationMode, ConfirmationOptions,
+    };
     use crate::{
+        error::Error,
         rpc::Value,
-        transports::test::TestTransport,
+        sleeper::{NoopSleeper, StepSleeper},
+        transports::test::{block_response, TestTransport},
         types::{Address, TransactionReceipt, TransactionRequest, H256, U64},
     };
     use serde_json::json;
@@ -163,6 +323,8 @@ mod tests {
             logs_bloom: Default::default(),
             transaction_type: None,
             effective_gas_price: Default::default(),
+            blob_gas_used: None,
+            blob_gas_price: None,
         };
 
         let poll_interval = Duration::from_secs(0);
@@ -223,4 +385,84 @@ mod tests {
         transport.assert_no_more_requests();
         assert_eq!(confirmation, Ok(transaction_receipt));
     }
+
+    #[test]
+    fn send_transaction_with_confirmation_gives_up() {
+        let mut transport = TestTransport::default();
+        transport.add_response(Value::String(
+            r#"0x0000000000000000000000000000000000000000000000000000000000000111"#.into(),
+        ));
+        transport.add_response(Value::String("0x123".into()));
+        transport.add_response(Value::Array(vec![]));
+
+        let future = send_transaction_with_options(
+            &transport,
+            TransactionRequest {
+                from: Address::from_low_u64_be(0x123),
+                ..Default::default()
+            },
+            ConfirmationOptions::new(1).sleeper(StepSleeper::new(0)),
+        );
+
+        assert_eq!(futures::executor::block_on(future), Err(Error::NotYet));
+        transport.assert_request(
+            "eth_sendTransaction",
+            &[r#"{"from":"0x0000000000000000000000000000000000000123"}"#.into()],
+        );
+        transport.assert_request("eth_newBlockFilter", &[]);
+        transport.assert_request("eth_getFilterChanges", &[r#""0x123""#.into()]);
+        transport.assert_no_more_requests();
+    }
+
+    #[test]
+    fn send_transaction_with_polling_confirmation() {
+        let mut transport = TestTransport::default();
+        let receipt = TransactionReceipt {
+            block_hash: Some(H256::from_low_u64_be(0xb2)),
+            block_number: Some(2.into()),
+            status: Some(1.into()),
+            ..Default::default()
+        };
+        let block = |hash: u64| block_response(2, H256::from_low_u64_be(hash), H256::zero());
+        transport.add_response(Value::String(
+            r#"0x0000000000000000000000000000000000000000000000000000000000000111"#.into(),
+        ));
+        transport.add_response(Value::Null);
+        transport.add_response(json!(receipt));
+        transport.add_response(Value::String("0x2".into()));
+        transport.add_response(json!(receipt));
+        transport.add_response(Value::String("0x3".into()));
+        transport.add_response(block(0xc2));
+        transport.add_response(json!(receipt));
+        transport.add_response(Value::String("0x3".into()));
+        transport.add_response(block(0xb2));
+
+        let future = send_transaction_with_options(
+            &transport,
+            TransactionRequest {
+                from: Address::from_low_u64_be(0x123),
+                ..Default::default()
+            },
+            ConfirmationOptions::new(1)
+                .sleeper(NoopSleeper)
+                .mode(ConfirmationMode::Polling),
+        );
+
+        assert_eq!(futures::executor::block_on(future), Ok(receipt));
+        transport.assert_request(
+            "eth_sendTransaction",
+            &[r#"{"from":"0x0000000000000000000000000000000000000123"}"#.into()],
+        );
+        let receipt_request = || [r#""0x0000000000000000000000000000000000000000000000000000000000000111""#.into()];
+        transport.assert_request("eth_getTransactionReceipt", &receipt_request());
+        transport.assert_request("eth_getTransactionReceipt", &receipt_request());
+        transport.assert_request("eth_blockNumber", &[]);
+        transport.assert_request("eth_getTransactionReceipt", &receipt_request());
+        transport.assert_request("eth_blockNumber", &[]);
+        transport.assert_request("eth_getBlockByNumber", &[r#""0x2""#.into(), "false".into()]);
+        transport.assert_request("eth_getTransactionReceipt", &receipt_request());
+        transport.assert_request("eth_blockNumber", &[]);
+        transport.assert_request("eth_getBlockByNumber", &[r#""0x2""#.into(), "false".into()]);
+        transport.assert_no_more_requests();
+    }
 }
@@ -2,14 +2,19 @@
 use crate::prelude::*;
 use crate::{
     api::{Eth, Namespace},
-    confirm,
+    confirm::{self, ConfirmationMode, ConfirmationOptions},
     contract::{tokens::Tokenize, Contract, Options},
     error,
+    nonce::NonceManager,
+    sleeper::{NoopSleeper, Sleeper},
     types::{Address, Bytes, TransactionReceipt, TransactionRequest},
     Transport,
 };
 #[cfg(feature = "signing")]
-use crate::{signing::Key, types::TransactionParameters};
+use crate::{
+    signing::Key,
+    types::{TransactionParameters, DEFAULT_GAS_MULTIPLIER_PERCENT},
+};
 use alloc::collections::BTreeMap;
 use core::time;
 use futures::{Future, TryFutureExt};
@@ -17,20 +22,22 @@ use futures::{Future, TryFutureExt};
 pub use crate::contract::error::deploy::Error;
 
 /// A configuration builder for contract deployment.
+///
+/// Confirmations are polled again right away, without waiting `poll_interval`, unless a [`Sleeper`]
+/// is set with [`Builder::sleeper`].
 #[derive(Debug)]
-pub struct Builder<T: Transport> {
+pub struct Builder<T: Transport, S: Sleeper = NoopSleeper> {
     pub(crate) eth: Eth<T>,
     pub(crate) abi: ethabi::Contract,
     pub(crate) options: Options,
-    pub(crate) confirmations: usize,
-    pub(crate) poll_interval: time::Duration,
+    pub(crate) confirmations: ConfirmationOptions<S>,
     pub(crate) linker: BTreeMap<String, Address>,
 }
 
-impl<T: Transport> Builder<T> {
+impl<T: Transport, S: Sleeper> Builder<T, S> {
     /// Number of confirmations required after code deployment.
     pub fn confirmations(mut self, confirmations: usize) -> Self {
-        self.confirmations = confirmations;
+        self.confirmations.confirmations = confirmations;
         self
     }
 
@@ -42,10 +49,35 @@ impl<T: Transport> Builder<T> {
 
     /// Confirmations poll interval.
     pub fn poll_interval(mut self, interval: time::Duration) -> Self {
-        self.poll_interval = interval;
+        self.confirmations = self.confirmations.poll_interval(interval);
+        self
+    }
+
+    /// Sleeper used to wait `poll_interval` between confirmation polls.
+    pub fn sleeper<S2: Sleeper>(self, sleeper: S2) -> Builder<T, S2> {
+        Builder {
+            eth: self.eth,
+            abi: self.abi,
+            options: self.options,
+            confirmations: self.confirmations.sleeper(sleeper),
+            linker: self.linker,
+        }
+    }
+
+    /// How confirmations are awaited, see [`ConfirmationMode`].
+    pub fn confirmation_mode(mut self, mode: ConfirmationMode) -> Self {
+        self.confirmations = self.confirmations.mode(mode);
         self
     }
 
+    /// Takes the deployment nonce of `from` from `nonces`, unless the options already set one.
+    pub async fn nonce_from(mut self, nonces: &mut NonceManager, from: Address) -> Result<Self, Error> {
+        if self.options.nonce.is_none() {
+            self.options.nonce = Some(nonces.next(&self.eth, from).await?);
+        }
+        Ok(self)
+    }
+
     /// Execute deployment passing code and contructor parameters.
     pub async fn execute<P, V>(self, code: V, params: P, from: Address) -> Result<Contract<T>, Error>
     where
@@ -53,11 +85,10 @@ impl<T: Transport> Builder<T> {
         V: AsRef<str>,
     {
         let transport = self.eth.transport().clone();
-        let poll_interval = self.poll_interval;
-        let confirmations = self.confirmations;
+        let confirmations = self.confirmations.clone();
 
         self.do_execute(code, params, from, move |tx| {
-            confirm::send_transaction_with_confirmation(transport, tx, poll_interval, confirmations)
+            confirm::send_transaction_with_options(transport, tx, confirmations)
         })
         .await
     }
@@ -79,19 +110,13 @@ impl<T: Transport> Builder<T> {
         V: AsRef<str>,
     {
         let transport = self.eth.transport().clone();
-        let poll_interval = self.poll_interval;
-        let confirmations = self.confirmations;
+        let confirmations = self.confirmations.clone();
 
         self.do_execute(code, params, from, move |tx| {
             crate::api::Personal::new(transport.clone())
                 .sign_transaction(tx, password)
                 .and_then(move |signed_tx| {
-                    confirm::send_raw_transaction_with_confirmation(
-                        transport,
-                        signed_tx.raw,
-                        poll_interval,
-                        confirmations,
-                    )
+                    confirm::send_raw_transaction_with_options(transport, signed_tx.raw, confirmations)
                 })
         })
         .await
@@ -124,14 +149,14 @@ impl<T: Transport> Builder<T> {
         K: Key,
     {
         let transport = self.eth.transport().clone();
-        let poll_interval = self.poll_interval;
-        let confirmations = self.confirmations;
+        let confirmations = self.confirmations.clone();
 
         self.do_execute(code, params, from.address(), move |tx| async move {
             let tx = TransactionParameters {
                 nonce: tx.nonce,
                 to: tx.to,
-                gas: tx.gas.unwrap_or_else(|| 1_000_000.into()),
+                gas: tx.gas,
+                gas_multiplier_percent: DEFAULT_GAS_MULTIPLIER_PERCENT,
                 gas_price: tx.gas_price,
                 value: tx.value.unwrap_or_else(|| 0.into()),
                 data: tx
@@ -142,17 +167,15 @@ impl<T: Transport> Builder<T> {
                 access_list: tx.access_list,
                 max_fee_per_gas: tx.max_fee_per_gas,
                 max_priority_fee_per_gas: tx.max_priority_fee_per_gas,
+                max_fee_per_blob_gas: None,
+                blob_versioned_hashes: None,
+                blob_sidecar: None,
+                authorization_list: None,
             };
             let signed_tx = crate::api::Accounts::new(transport.clone())
                 .sign_transaction(tx, from)
                 .await?;
-            confirm::send_raw_transaction_with_confirmation(
-                transport,
-                signed_tx.raw_transaction,
-                poll_interval,
-                confirmations,
-            )
-            .await
+            confirm::send_raw_transaction_with_options(transport, signed_tx.raw_transaction, confirmations).await
         })
         .await
     }
@@ -1,7 +1,7 @@
 //! Contract call/query error.
 use crate::prelude::*;
 
-use crate::error::Error as ApiError;
+use crate::{contract::revert::RevertReason, error::Error as ApiError};
 use derive_more::{Display, From};
 use ethabi::Error as EthError;
 
@@ -26,6 +26,9 @@ pub enum Error {
     JsonDecode(String),
     /// Contract does not support this interface.
     InterfaceUnsupported,
+    /// The call reverted.
+    #[display(fmt = "Reverted: {}", _0)]
+    Revert(RevertReason),
 }
 
 #[cfg(feature = "std")]
@@ -38,6 +41,7 @@ impl std::error::Error for Error {
             Error::Deployment(ref e) => Some(e),
             Error::InterfaceUnsupported => None,
             Error::JsonDecode(_) => None,
+            Error::Revert(_) => None,
         }
     }
 }
@@ -5,12 +5,13 @@ use serde::Deserialize;
 
 use crate::{
     api::{Eth, Namespace},
-    confirm,
+    confirm::{self, ConfirmationOptions},
     contract::tokens::{Detokenize, Tokenize},
     futures::Future,
+    sleeper::{NoopSleeper, Sleeper},
     types::{
-        AccessList, Address, BlockId, Bytes, CallRequest, FilterBuilder, TransactionCondition, TransactionReceipt,
-        TransactionRequest, H256, U256, U64,
+        AccessList, Address, BlockId, BlockOverrides, Bytes, CallRequest, FilterBuilder, StateOverride,
+        TransactionCondition, TransactionReceipt, TransactionRequest, H256, U256, U64,
     },
     Transport,
 };
@@ -20,6 +21,7 @@ use core::{hash::Hash, time};
 pub mod deploy;
 // pub mod ens;
 mod error;
+pub mod revert;
 pub mod tokens;
 
 pub use crate::contract::error::Error;
@@ -77,6 +79,21 @@ impl Options {
     }
 }
 
+/// State and block header overrides of a constant call, see [`Contract::query_with_overrides`].
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct CallOverrides {
+    /// Account state overrides
+    pub state: StateOverride,
+    /// Block header overrides
+    pub block: Option<BlockOverrides>,
+}
+
+impl From<StateOverride> for CallOverrides {
+    fn from(state: StateOverride) -> Self {
+        CallOverrides { state, block: None }
+    }
+}
+
 /// Ethereum Contract Interface
 #[derive(Debug, Clone)]
 pub struct Contract<T: Transport> {
@@ -93,8 +110,9 @@ impl<T: Transport> Contract<T> {
             eth,
             abi,
             options: Options::default(),
-            confirmations: 1,
-            poll_interval: time::Duration::from_secs(7),
+            confirmations: ConfirmationOptions::new(1)
+                .poll_interval(time::Duration::from_secs(7))
+                .sleeper(NoopSleeper),
             linker: BTreeMap::default(),
         })
     }
@@ -110,8 +128,9 @@ impl<T: Transport> Contract<T> {
             eth,
             abi,
             options: Options::default(),
-            confirmations: 1,
-            poll_interval: time::Duration::from_secs(7),
+            confirmations: ConfirmationOptions::new(1)
+                .poll_interval(time::Duration::from_secs(7))
+                .sleeper(NoopSleeper),
             linker,
         })
     }
@@ -175,17 +194,15 @@ impl<T: Transport> Contract<T> {
             .map_err(Error::from)
     }
 
-    /// Execute a contract function and wait for confirmations
+    /// Execute a contract function and wait for confirmations, as set by `confirmations`
     pub async fn call_with_confirmations(
         &self,
         func: &str,
         params: impl Tokenize,
         from: Address,
         options: Options,
-        confirmations: usize,
+        confirmations: ConfirmationOptions<impl Sleeper>,
     ) -> crate::error::Result<TransactionReceipt> {
-        let poll_interval = time::Duration::from_secs(1);
-
         let fn_data = self
             .abi
             .function(func)
@@ -207,13 +224,7 @@ impl<T: Transport> Contract<T> {
             max_fee_per_gas: options.max_fee_per_gas,
             max_priority_fee_per_gas: options.max_priority_fee_per_gas,
         };
-        confirm::send_transaction_with_confirmation(
-            self.eth.transport().clone(),
-            transaction_request,
-            poll_interval,
-            confirmations,
-        )
-        .await
+        confirm::send_transaction_with_options(self.eth.transport().clone(), transaction_request, confirmations).await
     }
 
     /// Estimate gas required for this function call.
@@ -239,7 +250,12 @@ impl<T: Transport> Contract<T> {
                 None,
             )
             .await
-            .map_err(Into::into)
+            .map_err(|err| revert::decode_error(err, Some(&self.abi)))
+    }
+
+    /// Recovers why a mined transaction calling this contract failed, see [`revert::replay`].
+    pub async fn revert_reason(&self, hash: H256) -> Result<Option<revert::RevertReason>> {
+        revert::replay(&self.eth, hash, Some(&self.abi)).await
     }
 
     /// Call constant function
@@ -251,6 +267,46 @@ impl<T: Transport> Contract<T> {
         options: Options,
         block: B,
     ) -> impl Future<Output = Result<R>> + '_
+    where
+        R: Detokenize,
+        A: Into<Option<Address>>,
+        B: Into<Option<BlockId>>,
+        P: Tokenize,
+    {
+        self.do_query(func, params, from, options, block, None)
+    }
+
+    /// Call constant function with account state (and optionally block header fields) overridden.
+    ///
+    /// Useful to simulate the call against state that does not exist on chain yet, e.g. an approval
+    /// that has not been sent or mocked contract code.
+    pub fn query_with_overrides<R, A, B, P>(
+        &self,
+        func: &str,
+        params: P,
+        from: A,
+        options: Options,
+        block: B,
+        overrides: CallOverrides,
+    ) -> impl Future<Output = Result<R>> + '_
+    where
+        R: Detokenize,
+        A: Into<Option<Address>>,
+        B: Into<Option<BlockId>>,
+        P: Tokenize,
+    {
+        self.do_query(func, params, from, options, block, Some(overrides))
+    }
+
+    fn do_query<R, A, B, P>(
+        &self,
+        func: &str,
+        params: P,
+        from: A,
+        options: Options,
+        block: B,
+        overrides: Option<CallOverrides>,
+    ) -> impl Future<Output = Result<R>> + '_
     where
         R: Detokenize,
         A: Into<Option<Address>>,
@@ -266,28 +322,34 @@ impl<T: Transport> Contract<T> {
                     .map(|call| (call, function))
             })
             .map(|(call, function)| {
-                let call_future = self.eth.call(
-                    CallRequest {
-                        from: from.into(),
-                        to: Some(self.address),
-                        gas: options.gas,
-                        gas_price: options.gas_price,
-                        value: options.value,
-                        data: Some(Bytes(call)),
-                        transaction_type: options.transaction_type,
-                        access_list: options.access_list,
-                        max_fee_per_gas: options.max_fee_per_gas,
-                        max_priority_fee_per_gas: options.max_priority_fee_per_gas,
-                    },
-                    block.into(),
-                );
+                let req = CallRequest {
+                    from: from.into(),
+                    to: Some(self.address),
+                    gas: options.gas,
+                    gas_price: options.gas_price,
+                    value: options.value,
+                    data: Some(Bytes(call)),
+                    transaction_type: options.transaction_type,
+                    access_list: options.access_list,
+                    max_fee_per_gas: options.max_fee_per_gas,
+                    max_priority_fee_per_gas: options.max_priority_fee_per_gas,
+                };
+                let call_future = match overrides {
+                    Some(overrides) => {
+                        self.eth
+                            .call_with_overrides(req, block.into(), Some(overrides.state), overrides.block)
+                    }
+                    None => self.eth.call(req, block.into()),
+                };
                 (call_future, function)
             });
         // NOTE for the batch transport to work correctly, we must call `transport.execute` without ever polling the future,
         // hence it cannot be a fully `async` function.
         async {
             let (call_future, function) = result?;
-            let bytes = call_future.await?;
+            let bytes = call_future
+                .await
+                .map_err(|err| revert::decode_error(err, Some(&self.abi)))?;
             let output = function.decode_output(&bytes.0)?;
             R::from_tokens(output)
         }
@@ -345,18 +407,19 @@ mod contract_signing {
     use super::*;
     use crate::{
         api::Accounts,
+        error::ErrorKind,
+        nonce::NonceManager,
         signing,
-        types::{SignedTransaction, TransactionParameters},
+        types::{BlockNumber, SignedTransaction, TransactionParameters},
     };
 
     impl<T: Transport> Contract<T> {
-        async fn sign(
+        fn transaction(
             &self,
             func: &str,
             params: impl Tokenize,
             options: Options,
-            key: impl signing::Key,
-        ) -> crate::Result<SignedTransaction> {
+        ) -> crate::Result<TransactionParameters> {
             let fn_data = self
                 .abi
                 .function(func)
@@ -364,10 +427,10 @@ mod contract_signing {
                 // TODO [ToDr] SendTransactionWithConfirmation should support custom error type (so that we can return
                 // `contract::Error` instead of more generic `Error`.
                 .map_err(|err| crate::error::Error::Decoder(format!("{:?}", err)))?;
-            let accounts = Accounts::new(self.eth.transport().clone());
             let mut tx = TransactionParameters {
                 nonce: options.nonce,
                 to: Some(self.address),
+                gas: options.gas,
                 gas_price: options.gas_price,
                 data: Bytes(fn_data),
                 transaction_type: options.transaction_type,
@@ -376,13 +439,23 @@ mod contract_signing {
                 max_priority_fee_per_gas: options.max_priority_fee_per_gas,
                 ..Default::default()
             };
-            if let Some(gas) = options.gas {
-                tx.gas = gas;
-            }
             if let Some(value) = options.value {
                 tx.value = value;
             }
-            accounts.sign_transaction(tx, key).await
+            Ok(tx)
+        }
+
+        async fn sign(
+            &self,
+            func: &str,
+            params: impl Tokenize,
+            options: Options,
+            key: impl signing::Key,
+        ) -> crate::Result<SignedTransaction> {
+            let tx = self.transaction(func, params, options)?;
+            Accounts::new(self.eth.transport().clone())
+                .sign_transaction(tx, key)
+                .await
         }
 
         /// Submit contract call transaction to the transaction pool.
@@ -400,25 +473,103 @@ mod contract_signing {
             self.eth.send_raw_transaction(signed.raw_transaction).await
         }
 
+        /// Simulate a contract call transaction at the `pending` block, then submit it if it succeeds.
+        ///
+        /// The transaction is only signed and sent when the simulation doesn't revert, so a call sure to
+        /// fail doesn't burn gas. Returns the simulated output along with the transaction hash.
+        pub async fn signed_call_checked<R: Detokenize>(
+            &self,
+            func: &str,
+            params: impl Tokenize,
+            options: Options,
+            key: impl signing::Key,
+        ) -> Result<(R, H256)> {
+            let function = self.abi.function(func)?;
+            let tx = self.transaction(func, params, options)?;
+            let call = CallRequest {
+                from: Some(key.address()),
+                ..tx.clone().into()
+            };
+            let output = self
+                .eth
+                .call(call, Some(BlockNumber::Pending.into()))
+                .await
+                .map_err(|err| revert::decode_error(err, Some(&self.abi)))?;
+            let output = R::from_tokens(function.decode_output(&output.0)?)?;
+
+            let signed = Accounts::new(self.eth.transport().clone())
+                .sign_transaction(tx, key)
+                .await?;
+            let hash = self.eth.send_raw_transaction(signed.raw_transaction).await?;
+            Ok((output, hash))
+        }
+
+        /// Submit contract call transaction to the transaction pool, taking its nonce from `nonces`.
+        ///
+        /// A nonce set in `options` is used as is. An allocated nonce is given back to `nonces` if the
+        /// transaction can't be signed or is definitely rejected by the node. It stays allocated when
+        /// the transaction may have reached the pool, e.g. on a timeout or an "already known" error.
+        pub async fn signed_call_with_nonces(
+            &self,
+            func: &str,
+            params: impl Tokenize,
+            mut options: Options,
+            nonces: &mut NonceManager,
+            key: impl signing::Key,
+        ) -> crate::Result<H256> {
+            let from = key.address();
+            let allocated = match options.nonce {
+                Some(_) => None,
+                None => Some(nonces.next(&self.eth, from).await?),
+            };
+            options.nonce = options.nonce.or(allocated);
+
+            let signed = match self.sign(func, params, options, key).await {
+                Ok(signed) => signed,
+                Err(err) => {
+                    if let Some(nonce) = allocated {
+                        nonces.rewind(from, nonce);
+                    }
+                    return Err(err);
+                }
+            };
+            let result = self.eth.send_raw_transaction(signed.raw_transaction).await;
+            if let (Err(err), Some(nonce)) = (&result, allocated) {
+                let rejected = matches!(
+                    err.kind(),
+                    Some(
+                        ErrorKind::NonceTooLow
+                            | ErrorKind::NonceTooHigh
+                            | ErrorKind::ReplacementUnderpriced
+                            | ErrorKind::InsufficientFunds
+                            | ErrorKind::GasTooLow
+                    )
+                );
+                if rejected {
+                    nonces.rewind(from, nonce);
+                }
+            }
+            result
+        }
+
         /// Submit contract call transaction to the transaction pool and wait for the transaction to be included in a block.
         ///
-        /// This function will wait for block inclusion of the transaction before returning.
+        /// This function will wait for block inclusion of the transaction before returning,
+        /// polling as set by `confirmations`.
         // If you'd rather just submit transaction and receive it's hash, please use [`signed_call`] instead.
         pub async fn signed_call_with_confirmations(
             &self,
             func: &str,
             params: impl Tokenize,
             options: Options,
-            confirmations: usize,
+            confirmations: ConfirmationOptions<impl Sleeper>,
             key: impl signing::Key,
         ) -> crate::Result<TransactionReceipt> {
-            let poll_interval = time::Duration::from_secs(1);
             let signed = self.sign(func, params, options, key).await?;
 
-            confirm::send_raw_transaction_with_confirmation(
+            confirm::send_raw_transaction_with_options(
                 self.eth.transport().clone(),
                 signed.raw_transaction,
-                poll_interval,
                 confirmations,
             )
             .await
@@ -433,7 +584,7 @@ mod tests {
         api::{self, Namespace},
         rpc,
         transports::test::TestTransport,
-        types::{Address, BlockId, BlockNumber, H256, U256},
+        types::{AccountOverride, Address, BlockId, BlockNumber, StateOverride, H256, U256},
         Transport,
     };
 
@@ -534,6 +685,46 @@ mod tests {
         assert_eq!(result, "Hello World!".to_owned());
     }
 
+    #[test]
+    fn should_query_with_state_overrides() {
+        // given
+        let mut transport = TestTransport::default();
+        transport.set_response(rpc::Value::String(
+            "0x0000000000000000000000000000000000000000000000000000000000000020".into(),
+        ));
+
+        let result: U256 = {
+            let token = contract(&transport);
+            let overrides = StateOverride::from([(
+                Address::from_low_u64_be(1),
+                AccountOverride::default().code(vec![0x60, 0x20].into()),
+            )]);
+
+            // when
+            futures::executor::block_on(token.query_with_overrides(
+                "balanceOf",
+                Address::from_low_u64_be(5),
+                None,
+                Options::default(),
+                None,
+                overrides.into(),
+            ))
+            .unwrap()
+        };
+
+        // then
+        transport.assert_request(
+            "eth_call",
+            &[
+                "{\"data\":\"0x70a082310000000000000000000000000000000000000000000000000000000000000005\",\"to\":\"0x0000000000000000000000000000000000000001\"}".into(),
+                "\"latest\"".into(),
+                "{\"0x0000000000000000000000000000000000000001\":{\"code\":\"0x6020\"}}".into(),
+            ],
+        );
+        transport.assert_no_more_requests();
+        assert_eq!(result, 0x20.into());
+    }
+
     #[test]
     fn should_call_a_contract_function() {
         // given
@@ -601,4 +792,142 @@ mod tests {
         transport.assert_no_more_requests();
         assert_eq!(result, 0x20.into());
     }
+
+    #[cfg(feature = "signing")]
+    #[test]
+    fn should_send_checked_call_after_simulation() {
+        use crate::signing::TestKey;
+        use hex_literal::hex;
+
+        // given
+        let mut transport = TestTransport::default();
+        transport.add_response(rpc::Value::String(
+            "0x0000000000000000000000000000000000000000000000000000000000000001".into(),
+        ));
+        transport.add_response(rpc::Value::String("0x1".into()));
+        transport.add_response(rpc::Value::String(format!("{:?}", H256::from_low_u64_be(0xaa))));
+
+        let options = Options::with(|options| {
+            options.nonce = Some(0.into());
+            options.gas = Some(50_000.into());
+            options.gas_price = Some(1.into());
+        });
+        let (result, hash): (bool, H256) = {
+            let token = contract(&transport);
+
+            // when
+            futures::executor::block_on(token.signed_call_checked(
+                "approve",
+                (Address::from_low_u64_be(5), U256::from(10)),
+                options,
+                TestKey,
+            ))
+            .unwrap()
+        };
+
+        // then
+        transport.assert_request("eth_call", &["{\"from\":\"0x0000000000000000000000000000000000000123\",\"to\":\"0x0000000000000000000000000000000000000001\",\"gas\":\"0xc350\",\"gasPrice\":\"0x1\",\"value\":\"0x0\",\"data\":\"0x095ea7b30000000000000000000000000000000000000000000000000000000000000005000000000000000000000000000000000000000000000000000000000000000a\"}".into(), "\"pending\"".into()]);
+        transport.assert_request("eth_chainId", &[]);
+        let signed = futures::executor::block_on(api::Accounts::new(&transport).sign_transaction(
+            crate::types::TransactionParameters {
+                nonce: Some(0.into()),
+                to: Some(Address::from_low_u64_be(1)),
+                gas: Some(50_000.into()),
+                gas_price: Some(1.into()),
+                data: hex!("095ea7b30000000000000000000000000000000000000000000000000000000000000005000000000000000000000000000000000000000000000000000000000000000a").to_vec().into(),
+                chain_id: Some(1),
+                ..Default::default()
+            },
+            TestKey,
+        ))
+        .unwrap();
+        transport.assert_request(
+            "eth_sendRawTransaction",
+            &[format!("\"0x{}\"", hex::encode(signed.raw_transaction.0))],
+        );
+        transport.assert_no_more_requests();
+        assert!(result);
+        assert_eq!(hash, H256::from_low_u64_be(0xaa));
+    }
+
+    #[cfg(feature = "signing")]
+    #[test]
+    fn should_not_send_reverting_checked_call() {
+        use crate::signing::TestKey;
+
+        // given
+        let mut transport = TestTransport::default();
+        transport.add_error_with_data(3, "execution reverted", rpc::Value::String("0x".into()));
+
+        let result = {
+            let token = contract(&transport);
+
+            // when
+            futures::executor::block_on(token.signed_call_checked::<bool>(
+                "approve",
+                (Address::from_low_u64_be(5), U256::from(10)),
+                Options::default(),
+                TestKey,
+            ))
+        };
+
+        // then
+        assert!(matches!(
+            result,
+            Err(super::Error::Revert(super::revert::RevertReason::Raw(_)))
+        ));
+        transport.assert_request("eth_call", &["{\"from\":\"0x0000000000000000000000000000000000000123\",\"to\":\"0x0000000000000000000000000000000000000001\",\"value\":\"0x0\",\"data\":\"0x095ea7b30000000000000000000000000000000000000000000000000000000000000005000000000000000000000000000000000000000000000000000000000000000a\"}".into(), "\"pending\"".into()]);
+        transport.assert_no_more_requests();
+    }
+
+    #[cfg(feature = "signing")]
+    fn send_with_nonces(code: i64, message: &str) -> crate::nonce::NonceManager {
+        use crate::signing::{Key, TestKey};
+
+        let mut transport = TestTransport::default();
+        transport.add_response(rpc::Value::String("0x5".into()));
+        transport.add_response(rpc::Value::String("0x1".into()));
+        transport.add_error(code, message);
+
+        let mut nonces = crate::nonce::NonceManager::new();
+        let options = Options::with(|options| {
+            options.gas = Some(50_000.into());
+            options.gas_price = Some(1.into());
+        });
+        let result = futures::executor::block_on(contract(&transport).signed_call_with_nonces(
+            "approve",
+            (Address::from_low_u64_be(5), U256::from(10)),
+            options,
+            &mut nonces,
+            TestKey,
+        ));
+
+        assert!(result.is_err());
+        transport.assert_request(
+            "eth_getTransactionCount",
+            &[format!("\"{:?}\"", TestKey.address()), "\"pending\"".into()],
+        );
+        transport.assert_request("eth_chainId", &[]);
+        nonces
+    }
+
+    #[cfg(feature = "signing")]
+    #[test]
+    fn should_rewind_nonce_of_rejected_call() {
+        use crate::signing::{Key, TestKey};
+
+        let nonces = send_with_nonces(-32000, "nonce too low");
+        assert_eq!(nonces.peek(TestKey.address()), Some(5.into()));
+    }
+
+    #[cfg(feature = "signing")]
+    #[test]
+    fn should_keep_nonce_of_call_that_may_be_pending() {
+        use crate::signing::{Key, TestKey};
+
+        let nonces = send_with_nonces(-32000, "already known");
+        assert_eq!(nonces.peek(TestKey.address()), Some(6.into()));
+        let nonces = send_with_nonces(-32000, "request timed out");
+        assert_eq!(nonces.peek(TestKey.address()), Some(6.into()));
+    }
 }
@@ -0,0 +1,262 @@
+//! Decoding of revert data.
+use crate::prelude::*;
+use crate::{
+    api::Eth,
+    error::{Error as ApiError, Revert},
+    types::{BlockId, BlockNumber, Bytes, CallRequest, TransactionId, H256, U256},
+    Transport,
+};
+use ethabi::{ParamType, Token};
+
+/// Selector of `Panic(uint256)`, raised by failed assertions and arithmetic errors.
+pub const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+/// Decoded reason of a revert.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RevertReason {
+    /// `Error(string)`, raised by `revert("reason")` and `require(condition, "reason")`.
+    Message(String),
+    /// `Panic(uint256)`, raised by the compiler with one of the Solidity panic codes.
+    Panic(U256),
+    /// Custom error defined in the contract's ABI.
+    Custom {
+        /// Name of the error.
+        name: String,
+        /// Decoded parameters of the error.
+        params: Vec<Token>,
+    },
+    /// Revert data that couldn't be decoded, empty for a bare `revert()`.
+    Raw(Bytes),
+}
+
+impl RevertReason {
+    /// Decodes revert data, looking up custom errors in `abi` if given.
+    pub fn decode(data: &[u8], abi: Option<&ethabi::Contract>) -> Self {
+        if data.len() < 4 {
+            return RevertReason::Raw(data.to_vec().into());
+        }
+        let (selector, params) = data.split_at(4);
+
+        if selector == Revert::ERROR_SELECTOR {
+            if let Ok(Some(Token::String(message))) = ethabi::decode(&[ParamType::String], params).map(|mut t| t.pop())
+            {
+                return RevertReason::Message(message);
+            }
+        }
+        if selector == PANIC_SELECTOR {
+            if let Ok(Some(Token::Uint(code))) = ethabi::decode(&[ParamType::Uint(256)], params).map(|mut t| t.pop()) {
+                return RevertReason::Panic(code);
+            }
+        }
+        let custom = abi
+            .into_iter()
+            .flat_map(|abi| abi.errors())
+            .filter(|error| error.signature()[..4] == *selector)
+            .find_map(|error| error.decode(params).ok().map(|params| (error.name.clone(), params)));
+        match custom {
+            Some((name, params)) => RevertReason::Custom { name, params },
+            None => RevertReason::Raw(data.to_vec().into()),
+        }
+    }
+}
+
+impl core::fmt::Display for RevertReason {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            RevertReason::Message(message) => write!(f, "{}", message),
+            RevertReason::Panic(code) => write!(f, "panic 0x{:02x}: {}", code, panic_description(*code)),
+            RevertReason::Custom { name, params } => {
+                write!(f, "{}(", name)?;
+                for (i, param) in params.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", param)?;
+                }
+                write!(f, ")")
+            }
+            RevertReason::Raw(data) if data.0.is_empty() => write!(f, "no reason"),
+            RevertReason::Raw(data) => write!(f, "0x{}", hex::encode(&data.0)),
+        }
+    }
+}
+
+/// Meaning of a Solidity panic code.
+pub fn panic_description(code: U256) -> &'static str {
+    if code > U256::from(u8::MAX) {
+        return "unknown panic code";
+    }
+    match code.low_u64() {
+        0x00 => "generic compiler panic",
+        0x01 => "assertion failed",
+        0x11 => "arithmetic overflow or underflow",
+        0x12 => "division or modulo by zero",
+        0x21 => "invalid enum value",
+        0x22 => "invalid storage byte array encoding",
+        0x31 => "pop on an empty array",
+        0x32 => "array index out of bounds",
+        0x41 => "too much memory allocated",
+        0x51 => "call to a zero-initialized internal function",
+        _ => "unknown panic code",
+    }
+}
+
+/// Turns a revert reported by the node into `contract::Error::Revert`.
+pub(crate) fn decode_error(error: ApiError, abi: Option<&ethabi::Contract>) -> super::Error {
+    match error {
+        ApiError::Revert(revert) => super::Error::Revert(RevertReason::decode(&revert.data.0, abi)),
+        error => super::Error::Api(error),
+    }
+}
+
+/// Recovers why a mined transaction failed, by replaying it with `eth_call` on the state of the
+/// parent of its block.
+///
+/// Returns `None` if the transaction is unknown or pending, or if the replay doesn't revert, e.g.
+/// because it ran out of gas or depended on an earlier transaction of the same block.
+pub async fn replay<T: Transport>(
+    eth: &Eth<T>,
+    hash: H256,
+    abi: Option<&ethabi::Contract>,
+) -> super::Result<Option<RevertReason>> {
+    let tx = match eth.transaction(TransactionId::Hash(hash)).await? {
+        Some(tx) => tx,
+        None => return Ok(None),
+    };
+    let block = match tx.block_number {
+        Some(block) => BlockId::Number(BlockNumber::Number(block.saturating_sub(1.into()))),
+        None => return Ok(None),
+    };
+    let call = CallRequest {
+        from: tx.from,
+        to: tx.to,
+        gas: Some(tx.gas),
+        value: Some(tx.value),
+        data: Some(tx.input),
+        ..Default::default()
+    };
+    match eth.call(call, Some(block)).await {
+        Ok(_) => Ok(None),
+        Err(ApiError::Revert(revert)) => Ok(Some(RevertReason::decode(&revert.data.0, abi))),
+        Err(error) => Err(error.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{api::Namespace, rpc::Value, transports::test::TestTransport};
+    use futures::executor::block_on;
+    use hex_literal::hex;
+    use serde_json::json;
+
+    const ABI: &[u8] = br#"[{
+        "type": "error",
+        "name": "InsufficientBalance",
+        "inputs": [{"name": "available", "type": "uint256"}, {"name": "required", "type": "uint256"}]
+    }]"#;
+
+    #[test]
+    fn decodes_revert_data() {
+        let abi: ethabi::Contract = serde_json::from_slice(ABI).unwrap();
+
+        let message = hex!("08c379a0000000000000000000000000000000000000000000000000000000000000002000000000000000000000000000000000000000000000000000000000000000096e6f74206f776e65720000000000000000000000000000000000000000000000");
+        assert_eq!(
+            RevertReason::decode(&message, None),
+            RevertReason::Message("not owner".into())
+        );
+
+        let panic = hex!("4e487b710000000000000000000000000000000000000000000000000000000000000011");
+        let reason = RevertReason::decode(&panic, None);
+        assert_eq!(reason, RevertReason::Panic(0x11.into()));
+        assert_eq!(reason.to_string(), "panic 0x11: arithmetic overflow or underflow");
+
+        let error = &abi.errors_by_name("InsufficientBalance").unwrap()[0];
+        let custom = error.encode(&[Token::Uint(1.into()), Token::Uint(2.into())]).unwrap();
+        assert_eq!(
+            RevertReason::decode(&custom, Some(&abi)),
+            RevertReason::Custom {
+                name: "InsufficientBalance".into(),
+                params: vec![Token::Uint(1.into()), Token::Uint(2.into())],
+            }
+        );
+        assert_eq!(RevertReason::decode(&custom, None), RevertReason::Raw(custom.into()));
+        assert_eq!(RevertReason::decode(&[], None).to_string(), "no reason");
+    }
+
+    #[test]
+    fn replays_failed_transaction() {
+        let mut transport = TestTransport::default();
+        transport.add_response(json!({
+            "hash": H256::from_low_u64_be(0xaa),
+            "nonce": "0x0",
+            "blockHash": H256::from_low_u64_be(0xb),
+            "blockNumber": "0xb",
+            "transactionIndex": "0x0",
+            "from": "0x0000000000000000000000000000000000000123",
+            "to": "0x0000000000000000000000000000000000000456",
+            "value": "0x0",
+            "gas": "0x5208",
+            "gasPrice": "0x1",
+            "input": "0x1234"
+        }));
+        transport.add_error_with_data(
+            3,
+            "execution reverted",
+            Value::String("0x4e487b710000000000000000000000000000000000000000000000000000000000000001".into()),
+        );
+
+        let eth = Eth::new(&transport);
+        let reason = block_on(replay(&eth, H256::from_low_u64_be(0xaa), None)).unwrap();
+        assert_eq!(reason, Some(RevertReason::Panic(1.into())));
+
+        transport.assert_request(
+            "eth_getTransactionByHash",
+            &[r#""0x00000000000000000000000000000000000000000000000000000000000000aa""#.into()],
+        );
+        transport.assert_request(
+            "eth_call",
+            &[
+                r#"{"from":"0x0000000000000000000000000000000000000123","to":"0x0000000000000000000000000000000000000456","gas":"0x5208","value":"0x0","data":"0x1234"}"#.into(),
+                r#""0xa""#.into(),
+            ],
+        );
+        transport.assert_no_more_requests();
+    }
+
+    #[test]
+    fn replays_on_parent_block() {
+        let mut transport = TestTransport::default();
+        transport.add_response(json!({
+            "hash": H256::from_low_u64_be(0xaa),
+            "nonce": "0x0",
+            "blockHash": H256::from_low_u64_be(0x100),
+            "blockNumber": "0x100",
+            "transactionIndex": "0x3",
+            "from": "0x0000000000000000000000000000000000000123",
+            "to": "0x0000000000000000000000000000000000000456",
+            "value": "0x0",
+            "gas": "0x5208",
+            "gasPrice": "0x1",
+            "input": "0x"
+        }));
+        transport.add_response(json!("0x"));
+
+        let eth = Eth::new(&transport);
+        let reason = block_on(replay(&eth, H256::from_low_u64_be(0xaa), None)).unwrap();
+        assert_eq!(reason, None);
+
+        transport.assert_request(
+            "eth_getTransactionByHash",
+            &[r#""0x00000000000000000000000000000000000000000000000000000000000000aa""#.into()],
+        );
+        transport.assert_request(
+            "eth_call",
+            &[
+                r#"{"from":"0x0000000000000000000000000000000000000123","to":"0x0000000000000000000000000000000000000456","gas":"0x5208","value":"0x0","data":"0x"}"#.into(),
+                r#""0xff""#.into(),
+            ],
+        );
+        transport.assert_no_more_requests();
+    }
+}
@@ -1,5 +1,6 @@
 //! Web3 Error
 use crate::prelude::*;
+use crate::types::Bytes;
 use derive_more::{Display, From};
 use json::de::Error as SerdeError;
 #[cfg(feature = "std")]
@@ -47,9 +48,182 @@ pub enum Error {
     /// recovery error
     #[display(fmt = "Recovery error: {}", _0)]
     Recovery(crate::signing::RecoveryError),
+    /// signing error
+    #[display(fmt = "Signing error: {}", _0)]
+    Signing(crate::signing::SigningError),
     /// web3 internal error
     #[display(fmt = "Internal Web3 error")]
     Internal,
+    /// the operation is not complete yet and should be resumed later
+    #[display(fmt = "Not ready yet, try again later")]
+    NotYet,
+    /// a call or gas estimation reverted
+    #[display(fmt = "Execution reverted: {}", _0)]
+    #[from(ignore)]
+    Revert(Revert),
+    /// a transaction parameter required to sign offline is missing
+    #[display(fmt = "Missing transaction parameter: {}", _0)]
+    #[from(ignore)]
+    MissingParameter(&'static str),
+    /// a transaction parameter is inconsistent with the transaction type or the other parameters
+    #[display(fmt = "Invalid transaction parameter: {}", _0)]
+    #[from(ignore)]
+    InvalidParameter(&'static str),
+    /// the operation isn't supported, e.g. by a signer
+    #[display(fmt = "Unsupported: {}", _0)]
+    #[from(ignore)]
+    Unsupported(String),
+}
+
+/// Revert of a call or gas estimation, as reported by the node.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Revert {
+    /// Message of the node's error.
+    pub message: String,
+    /// Data returned by the reverted call, empty if the node didn't return any.
+    pub data: Bytes,
+}
+
+impl Revert {
+    /// Selector of `Error(string)`, used by `revert("reason")` and `require(condition, "reason")`.
+    pub const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+
+    /// Decodes the reason string of the revert, if any.
+    pub fn reason(&self) -> Option<String> {
+        let data = self.data.0.strip_prefix(&Self::ERROR_SELECTOR)?;
+        match ethabi::decode(&[ethabi::ParamType::String], data).ok()?.pop()? {
+            ethabi::Token::String(reason) => Some(reason),
+            _ => None,
+        }
+    }
+}
+
+impl core::fmt::Display for Revert {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self.reason() {
+            Some(reason) => write!(f, "{}", reason),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+/// Kind of a common failure, as classified by [`Error::kind`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// The nonce was already used by a mined transaction.
+    NonceTooLow,
+    /// The nonce is ahead of the sender's next nonce by more than the node accepts.
+    NonceTooHigh,
+    /// A pending transaction with the same nonce pays higher fees than the replacement.
+    ReplacementUnderpriced,
+    /// The sender can't pay for gas and value.
+    InsufficientFunds,
+    /// The gas limit is lower than the intrinsic gas of the transaction.
+    GasTooLow,
+    /// The node already has the transaction.
+    AlreadyKnown,
+    /// The call or transaction reverted.
+    ExecutionReverted,
+    /// The provider throttled the request.
+    RateLimited,
+    /// The node doesn't support the method.
+    MethodNotFound,
+}
+
+/// Lowercase fragments of the codes and messages returned by geth, erigon, nethermind, besu and
+/// hosted providers, checked in order. A fragment only matches at the start of a word, so
+/// "known transaction" doesn't match "unknown transaction".
+const ERROR_KINDS: &[(ErrorKind, &[&str])] = &[
+    (
+        ErrorKind::ReplacementUnderpriced,
+        &[
+            "replacement transaction underpriced",
+            "replacement underpriced",
+            "another transaction with same nonce",
+        ],
+    ),
+    (
+        ErrorKind::NonceTooLow,
+        &[
+            "nonce too low",
+            "nonce is too low",
+            "oldnonce",
+            "nonce has already been used",
+        ],
+    ),
+    (
+        ErrorKind::NonceTooHigh,
+        &["nonce too high", "nonce is too high", "nonce too far in the future"],
+    ),
+    (
+        ErrorKind::InsufficientFunds,
+        &[
+            "insufficient funds",
+            "insufficientfunds",
+            "upfront cost exceeds account balance",
+        ],
+    ),
+    (
+        ErrorKind::GasTooLow,
+        &[
+            "intrinsic gas too low",
+            "intrinsic gas exceeds gas limit",
+            "gastoolow",
+            "gas too low",
+        ],
+    ),
+    (
+        ErrorKind::AlreadyKnown,
+        &["already known", "alreadyknown", "known transaction", "already imported"],
+    ),
+    (ErrorKind::ExecutionReverted, &["execution reverted", "code: 3,"]),
+    (
+        ErrorKind::RateLimited,
+        &[
+            "code: 429,",
+            "rate limit",
+            "too many requests",
+            "request rate exceeded",
+            "compute units",
+            "daily request count exceeded",
+        ],
+    ),
+    (
+        ErrorKind::MethodNotFound,
+        &[
+            "code: -32601,",
+            "method not found",
+            "method not supported",
+            "unsupported method",
+            "does not exist/is not available",
+        ],
+    ),
+];
+
+impl Error {
+    /// Classifies the error into one of the common failures, so callers don't have to match on
+    /// provider-specific messages.
+    pub fn kind(&self) -> Option<ErrorKind> {
+        match self {
+            Error::Revert(_) => Some(ErrorKind::ExecutionReverted),
+            Error::Transport(TransportError::Code(429)) => Some(ErrorKind::RateLimited),
+            Error::Rpc(message) => {
+                let message = message.to_lowercase();
+                ERROR_KINDS
+                    .iter()
+                    .find(|(_, fragments)| fragments.iter().any(|fragment| starts_word(&message, fragment)))
+                    .map(|(kind, _)| *kind)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Whether `fragment` occurs in `message` without a letter right before it.
+fn starts_word(message: &str, fragment: &str) -> bool {
+    message
+        .match_indices(fragment)
+        .any(|(start, _)| !message[..start].ends_with(|c: char| c.is_alphabetic()))
 }
 
 #[cfg(feature = "std")]
@@ -57,10 +231,13 @@ impl std::error::Error for Error {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         use self::Error::*;
         match *self {
-            Unreachable | Decoder(_) | InvalidResponse(_) | Transport { .. } | Internal => None,
+            Unreachable | Decoder(_) | InvalidResponse(_) | Transport { .. } | Internal | NotYet => None,
+            Unsupported(_) => None,
+            Revert(_) | MissingParameter(_) | InvalidParameter(_) => None,
             Rpc(_) => None,
             Io(ref e) => Some(e),
             Recovery(ref e) => Some(e),
+            Signing(ref e) => Some(e),
         }
     }
 }
@@ -71,6 +248,12 @@ impl From<SerdeError> for Error {
     }
 }
 
+impl From<rlp::DecoderError> for Error {
+    fn from(err: rlp::DecoderError) -> Self {
+        Error::Decoder(format!("{:?}", err))
+    }
+}
+
 impl Clone for Error {
     fn clone(&self) -> Self {
         use self::Error::*;
@@ -83,7 +266,13 @@ impl Clone for Error {
             #[cfg(feature = "std")]
             Io(e) => Io(IoError::from(e.kind())),
             Recovery(e) => Recovery(e.clone()),
+            Signing(e) => Signing(e.clone()),
             Internal => Internal,
+            NotYet => NotYet,
+            Revert(e) => Revert(e.clone()),
+            MissingParameter(name) => MissingParameter(name),
+            InvalidParameter(name) => InvalidParameter(name),
+            Unsupported(s) => Unsupported(s.clone()),
         }
     }
 }
@@ -93,13 +282,92 @@ impl PartialEq for Error {
     fn eq(&self, other: &Self) -> bool {
         use self::Error::*;
         match (self, other) {
-            (Unreachable, Unreachable) | (Internal, Internal) => true,
+            (Unreachable, Unreachable) | (Internal, Internal) | (NotYet, NotYet) => true,
             (Decoder(a), Decoder(b)) | (InvalidResponse(a), InvalidResponse(b)) => a == b,
+            (Unsupported(a), Unsupported(b)) => a == b,
             (Transport(a), Transport(b)) => a == b,
             (Rpc(a), Rpc(b)) => a == b,
             (Io(a), Io(b)) => a.kind() == b.kind(),
             (Recovery(a), Recovery(b)) => a == b,
+            (Signing(a), Signing(b)) => a == b,
+            (Revert(a), Revert(b)) => a == b,
+            (MissingParameter(a), MissingParameter(b)) | (InvalidParameter(a), InvalidParameter(b)) => a == b,
             _ => false,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rpc(code: i32, message: &str) -> Error {
+        Error::Rpc(format!("RpcError {{ code: {}, message: {:?} }}", code, message))
+    }
+
+    #[test]
+    fn classifies_provider_errors() {
+        let cases = [
+            (rpc(-32000, "nonce too low"), Some(ErrorKind::NonceTooLow)),
+            (
+                rpc(-32010, "Transaction nonce is too low. Try incrementing the nonce."),
+                Some(ErrorKind::NonceTooLow),
+            ),
+            (rpc(-32010, "OldNonce"), Some(ErrorKind::NonceTooLow)),
+            (rpc(-32000, "nonce too high"), Some(ErrorKind::NonceTooHigh)),
+            (
+                rpc(-32000, "replacement transaction underpriced"),
+                Some(ErrorKind::ReplacementUnderpriced),
+            ),
+            (
+                rpc(
+                    -32000,
+                    "insufficient funds for gas * price + value: balance 0, tx cost 1",
+                ),
+                Some(ErrorKind::InsufficientFunds),
+            ),
+            (
+                rpc(-32004, "Upfront cost exceeds account balance"),
+                Some(ErrorKind::InsufficientFunds),
+            ),
+            (rpc(-32000, "intrinsic gas too low"), Some(ErrorKind::GasTooLow)),
+            (rpc(-32000, "already known"), Some(ErrorKind::AlreadyKnown)),
+            (rpc(-32010, "AlreadyKnown"), Some(ErrorKind::AlreadyKnown)),
+            (rpc(-32000, "known transaction: 0x00aa"), Some(ErrorKind::AlreadyKnown)),
+            (rpc(-32000, "unknown transaction"), None),
+            (rpc(-32000, "tracing is not available"), None),
+            (
+                rpc(3, "execution reverted: not owner"),
+                Some(ErrorKind::ExecutionReverted),
+            ),
+            (
+                rpc(429, "Your app has exceeded its compute units per second capacity"),
+                Some(ErrorKind::RateLimited),
+            ),
+            (
+                rpc(-32005, "daily request count exceeded, request rate limited"),
+                Some(ErrorKind::RateLimited),
+            ),
+            (
+                rpc(-32601, "the method eth_foo does not exist/is not available"),
+                Some(ErrorKind::MethodNotFound),
+            ),
+            (rpc(-32000, "header not found"), None),
+            (
+                Error::Transport(TransportError::Code(429)),
+                Some(ErrorKind::RateLimited),
+            ),
+            (Error::Unreachable, None),
+        ];
+        for (error, kind) in cases {
+            assert_eq!(error.kind(), kind, "{:?}", error);
+        }
+    }
+
+    #[test]
+    fn classifies_decoded_rpc_error() {
+        let response = br#"{"jsonrpc":"2.0","id":0,"error":{"code":-32000,"message":"nonce too low"}}"#;
+        let error = crate::helpers::json_rpc::decode_response::<String>(response).unwrap_err();
+        assert_eq!(error.kind(), Some(ErrorKind::NonceTooLow));
+    }
+}
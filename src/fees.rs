@@ -0,0 +1,229 @@
+//! EIP-1559 fee estimation.
+//!
+//! The next base fee is predicted from the latest block header, and the priority fee is taken
+//! from the rewards paid in recent blocks, as reported by `eth_feeHistory`.
+
+use crate::prelude::*;
+use crate::{
+    api::{Eth, Namespace},
+    error::{self, Error},
+    helpers::{self, CallFuture},
+    types::{BlockId, BlockNumber, U256},
+    Transport,
+};
+use serde::Deserialize;
+
+/// Number of recent blocks whose rewards are sampled by default.
+pub const DEFAULT_FEE_HISTORY_BLOCKS: u64 = 10;
+
+/// Priority fee used when the sampled blocks paid no rewards, 1 gwei.
+pub const DEFAULT_PRIORITY_FEE: U256 = U256([1_000_000_000, 0, 0, 0]);
+
+/// Base fee change denominator from EIP-1559, limiting the change to 12.5% per block.
+const BASE_FEE_MAX_CHANGE_DENOMINATOR: u64 = 8;
+
+/// Gas target is the gas limit divided by this elasticity multiplier.
+const ELASTICITY_MULTIPLIER: u64 = 2;
+
+/// How quickly a transaction should be mined, as a reward percentile of recent blocks.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum FeeSpeed {
+    /// The 10th percentile of rewards.
+    Slow,
+    /// The median reward.
+    #[default]
+    Normal,
+    /// The 90th percentile of rewards.
+    Fast,
+}
+
+impl FeeSpeed {
+    /// Reward percentile sampled for this speed.
+    pub fn percentile(self) -> u8 {
+        match self {
+            FeeSpeed::Slow => 10,
+            FeeSpeed::Normal => 50,
+            FeeSpeed::Fast => 90,
+        }
+    }
+}
+
+/// Estimated fees of an EIP-1559 transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Eip1559Fees {
+    /// Max fee per gas, twice the predicted base fee plus the priority fee.
+    pub max_fee_per_gas: U256,
+    /// Max priority fee per gas.
+    pub max_priority_fee_per_gas: U256,
+}
+
+/// Estimates EIP-1559 fees from recent blocks.
+#[derive(Debug, Clone)]
+pub struct FeeEstimator<T: Transport> {
+    eth: Eth<T>,
+    blocks: u64,
+    speed: FeeSpeed,
+}
+
+impl<T: Transport> FeeEstimator<T> {
+    /// Creates an estimator sampling the last `DEFAULT_FEE_HISTORY_BLOCKS` blocks at normal speed.
+    pub fn new(eth: Eth<T>) -> Self {
+        FeeEstimator {
+            eth,
+            blocks: DEFAULT_FEE_HISTORY_BLOCKS,
+            speed: FeeSpeed::default(),
+        }
+    }
+
+    /// Number of recent blocks whose rewards are sampled.
+    pub fn blocks(mut self, blocks: u64) -> Self {
+        self.blocks = blocks.max(1);
+        self
+    }
+
+    /// Reward percentile to target.
+    pub fn speed(mut self, speed: FeeSpeed) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    /// Estimates the fees of a transaction sent now.
+    ///
+    /// Fails if the latest block has no base fee, i.e. the chain doesn't support EIP-1559.
+    pub async fn estimate(&self) -> error::Result<Eip1559Fees> {
+        let (block, rewards) =
+            futures::future::try_join(self.eth.block(BlockId::Number(BlockNumber::Latest)), self.rewards()).await?;
+        let block = block.ok_or_else(|| Error::InvalidResponse("latest block not found".into()))?;
+        let base_fee = block
+            .base_fee_per_gas
+            .ok_or_else(|| Error::InvalidResponse("latest block has no base fee".into()))?;
+
+        let next_base_fee = next_base_fee(base_fee, block.gas_used, block.gas_limit);
+        let max_priority_fee_per_gas = median(rewards).unwrap_or(DEFAULT_PRIORITY_FEE);
+        Ok(Eip1559Fees {
+            max_fee_per_gas: next_base_fee * 2 + max_priority_fee_per_gas,
+            max_priority_fee_per_gas,
+        })
+    }
+
+    /// Rewards at the target percentile of the sampled blocks, skipping empty blocks.
+    async fn rewards(&self) -> error::Result<Vec<U256>> {
+        // `FeeHistory` can't be used: its gas used ratios are floats, which the no_std JSON
+        // codec can't handle. Percentiles are sent as integers for the same reason.
+        #[derive(Deserialize)]
+        struct Rewards {
+            reward: Option<Vec<Vec<U256>>>,
+        }
+
+        let block_count = U256::from(self.blocks);
+        let percentiles = [self.speed.percentile()];
+        let rewards: Rewards = CallFuture::new(self.eth.transport().execute(
+            "eth_feeHistory",
+            vec![
+                helpers::serialize(&block_count),
+                helpers::serialize(&BlockNumber::Latest),
+                helpers::serialize(&percentiles),
+            ],
+        ))
+        .await?;
+        Ok(rewards
+            .reward
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|block| block.first().copied())
+            .filter(|reward| !reward.is_zero())
+            .collect())
+    }
+}
+
+/// Predicts the base fee of the block following one with the given base fee and gas usage.
+pub fn next_base_fee(base_fee: U256, gas_used: U256, gas_limit: U256) -> U256 {
+    let gas_target = gas_limit / ELASTICITY_MULTIPLIER;
+    if gas_target.is_zero() || gas_used == gas_target {
+        return base_fee;
+    }
+    if gas_used > gas_target {
+        let delta = base_fee * (gas_used - gas_target) / gas_target / BASE_FEE_MAX_CHANGE_DENOMINATOR;
+        base_fee + delta.max(1.into())
+    } else {
+        let delta = base_fee * (gas_target - gas_used) / gas_target / BASE_FEE_MAX_CHANGE_DENOMINATOR;
+        base_fee - delta
+    }
+}
+
+fn median(mut values: Vec<U256>) -> Option<U256> {
+    values.sort();
+    values.get(values.len() / 2).copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        rpc::Value,
+        transports::test::{block_response, TestTransport},
+        types::H256,
+    };
+    use futures::executor::block_on;
+    use serde_json::json;
+
+    #[test]
+    fn predicts_next_base_fee() {
+        let base_fee = U256::from(1_000_000_000u64);
+        let gas_limit = U256::from(30_000_000u64);
+        assert_eq!(next_base_fee(base_fee, 15_000_000u64.into(), gas_limit), base_fee);
+        assert_eq!(
+            next_base_fee(base_fee, gas_limit, gas_limit),
+            U256::from(1_125_000_000u64)
+        );
+        assert_eq!(
+            next_base_fee(base_fee, U256::zero(), gas_limit),
+            U256::from(875_000_000u64)
+        );
+        assert_eq!(next_base_fee(1.into(), 15_000_001u64.into(), gas_limit), 2.into());
+    }
+
+    #[test]
+    fn estimates_fees() {
+        let mut transport = TestTransport::default();
+        let mut block = block_response(0x10, H256::from_low_u64_be(1), H256::zero());
+        block["gasUsed"] = json!("0x1c9c380");
+        block["gasLimit"] = json!("0x1c9c380");
+        block["baseFeePerGas"] = json!("0x3b9aca00");
+        transport.add_response(block);
+        transport.add_response(json!({
+            "oldestBlock": "0xe",
+            "baseFeePerGas": ["0x3b9aca00", "0x3b9aca00", "0x3b9aca00", "0x430e2340"],
+            "gasUsedRatio": [0.5, 0.25, 1.0],
+            "reward": [["0x2"], ["0x0"], ["0x5"]]
+        }));
+
+        let estimator = FeeEstimator::new(Eth::new(&transport)).blocks(3).speed(FeeSpeed::Fast);
+        assert_eq!(
+            block_on(estimator.estimate()),
+            Ok(Eip1559Fees {
+                max_fee_per_gas: U256::from(2_250_000_005u64),
+                max_priority_fee_per_gas: 5.into(),
+            })
+        );
+
+        transport.assert_request("eth_getBlockByNumber", &[r#""latest""#.into(), "false".into()]);
+        transport.assert_request(
+            "eth_feeHistory",
+            &[r#""0x3""#.into(), r#""latest""#.into(), "[90]".into()],
+        );
+        transport.assert_no_more_requests();
+    }
+
+    #[test]
+    fn median_and_missing_block() {
+        assert_eq!(median(vec![]), None);
+        assert_eq!(median(vec![3.into(), 1.into(), 2.into()]), Some(2.into()));
+
+        let mut transport = TestTransport::default();
+        transport.add_response(Value::Null);
+        transport.add_response(json!({ "oldestBlock": "0x1", "baseFeePerGas": [], "gasUsedRatio": [] }));
+        let estimator = FeeEstimator::new(Eth::new(&transport));
+        assert!(matches!(block_on(estimator.estimate()), Err(Error::InvalidResponse(_))));
+    }
+}
@@ -26,6 +26,7 @@ pub fn serialize<T: erased_serde::Serialize>(t: &T) -> &dyn erased_serde::Serial
 pub struct CallFuture<T, F> {
     #[pin]
     inner: F,
+    reverts: bool,
     _marker: PhantomData<T>,
 }
 
@@ -34,9 +35,19 @@ impl<T, F> CallFuture<T, F> {
     pub fn new(inner: F) -> Self {
         CallFuture {
             inner,
+            reverts: false,
             _marker: PhantomData,
         }
     }
+
+    /// Create a new CallFuture of an `eth_call` or `eth_estimateGas` request, returning
+    /// `Error::Revert` when the call reverts.
+    pub fn with_reverts(inner: F) -> Self {
+        CallFuture {
+            reverts: true,
+            ..Self::new(inner)
+        }
+    }
 }
 
 impl<T, F> Future for CallFuture<T, F>
@@ -49,15 +60,23 @@ where
     fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
         let this = self.project();
         let x = ready!(this.inner.poll(ctx));
-        Poll::Ready(x.and_then(|data| json_rpc::decode_response(&data)))
+        let reverts = *this.reverts;
+        Poll::Ready(x.and_then(|data| match reverts {
+            true => json_rpc::decode_call_response(&data),
+            false => json_rpc::decode_response(&data),
+        }))
     }
 }
 
 pub(crate) mod json_rpc {
     use crate::prelude::*;
-    use crate::Error;
+    use crate::{error::Revert, types::Bytes, Error};
+    use core::fmt;
 
-    use serde::{Deserialize, Serialize};
+    use serde::{
+        de::{self, Visitor},
+        Deserialize, Deserializer, Serialize,
+    };
 
     #[derive(Serialize)]
     struct Request<'a, Params> {
@@ -73,10 +92,78 @@ pub(crate) mod json_rpc {
         error: Option<RpcError>,
     }
 
-    #[derive(Deserialize, Debug)]
+    #[derive(Deserialize)]
     pub struct RpcError {
         pub code: i32,
         pub message: String,
+        #[serde(default)]
+        pub data: Option<ErrorData>,
+    }
+
+    // Only code and message, `data` is surfaced by `Error::Revert`.
+    impl fmt::Debug for RpcError {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.debug_struct("RpcError")
+                .field("code", &self.code)
+                .field("message", &self.message)
+                .finish()
+        }
+    }
+
+    impl RpcError {
+        /// Turns a revert of `eth_call` or `eth_estimateGas` into `Error::Revert`.
+        fn into_revert(self) -> Error {
+            if self.code == 3 || self.message.to_lowercase().contains("revert") {
+                let data = self.data.and_then(|ErrorData(data)| data);
+                return Error::Revert(Revert {
+                    message: self.message,
+                    data: data.unwrap_or_default(),
+                });
+            }
+            Error::Rpc(format!("{self:?}"))
+        }
+    }
+
+    /// `data` of an error, kept only if it is hex encoded.
+    pub struct ErrorData(Option<Bytes>);
+
+    impl<'de> Deserialize<'de> for ErrorData {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            struct ErrorDataVisitor;
+
+            impl<'de> Visitor<'de> for ErrorDataVisitor {
+                type Value = ErrorData;
+
+                fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                    formatter.write_str("error data")
+                }
+
+                fn visit_str<E: de::Error>(self, value: &str) -> Result<ErrorData, E> {
+                    let data = value.strip_prefix("0x").and_then(|hex| hex::decode(hex).ok());
+                    Ok(ErrorData(data.map(Bytes)))
+                }
+
+                fn visit_unit<E: de::Error>(self) -> Result<ErrorData, E> {
+                    Ok(ErrorData(None))
+                }
+
+                fn visit_bool<E: de::Error>(self, _value: bool) -> Result<ErrorData, E> {
+                    Ok(ErrorData(None))
+                }
+
+                fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<ErrorData, A::Error> {
+                    while seq.next_element::<de::IgnoredAny>()?.is_some() {}
+                    Ok(ErrorData(None))
+                }
+
+                fn visit_map<A: de::MapAccess<'de>>(self, mut map: A) -> Result<ErrorData, A::Error> {
+                    while map.next_entry::<de::IgnoredAny, de::IgnoredAny>()?.is_some() {}
+                    Ok(ErrorData(None))
+                }
+            }
+
+            deserializer.deserialize_any(ErrorDataVisitor)
+        }
     }
 
     pub fn encode_request<Params: Serialize>(method: &str, params: Params) -> String {
@@ -91,13 +178,22 @@ pub(crate) mod json_rpc {
     }
 
     pub fn decode_response<'de, T: Deserialize<'de>>(response: &'de [u8]) -> Result<T, Error> {
-        let response: Response<T> = json::from_slice(response)
-            .or(Err(Error::Decoder("Failed to decode the rpc response".into())))?;
+        decode(response, |error| Error::Rpc(format!("{error:?}")))
+    }
+
+    /// Like `decode_response`, but a revert is returned as `Error::Revert`.
+    pub fn decode_call_response<'de, T: Deserialize<'de>>(response: &'de [u8]) -> Result<T, Error> {
+        decode(response, RpcError::into_revert)
+    }
+
+    fn decode<'de, T: Deserialize<'de>>(response: &'de [u8], error: fn(RpcError) -> Error) -> Result<T, Error> {
+        let response: Response<T> =
+            json::from_slice(response).or(Err(Error::Decoder("Failed to decode the rpc response".into())))?;
         if let Some(result) = response.result {
             return Ok(result);
         }
-        if let Some(error) = response.error {
-            return Err(Error::Rpc(format!("{error:?}")));
+        if let Some(rpc_error) = response.error {
+            return Err(error(rpc_error));
         }
         if let Ok(result) = json::from_str("null") {
             return Ok(result);
@@ -2,3 +2,6 @@
 
 #[cfg(all(feature = "pink", feature = "signing"))]
 pub mod pink;
+
+#[cfg(all(any(feature = "k256", test), feature = "signing"))]
+pub mod secret;
@@ -78,17 +78,3 @@ impl Key for KeyPair {
         self.address
     }
 }
-
-impl Key for &KeyPair {
-    fn sign(&self, message: &[u8; 32], chain_id: Option<u64>) -> Result<Signature, SigningError> {
-        (*self).sign(message, chain_id)
-    }
-
-    fn sign_message(&self, message: &[u8; 32]) -> Result<Signature, SigningError> {
-        (*self).sign_message(message)
-    }
-
-    fn address(&self) -> Address {
-        (*self).address()
-    }
-}
@@ -0,0 +1,106 @@
+//! impl Key for a pure-Rust secp256k1 secret key
+
+use crate::{
+    signing::{keccak256, Key, Signature, SigningError},
+    types::Address,
+};
+use core::convert::TryInto;
+use ethereum_types::H256;
+use k256::ecdsa::SigningKey;
+
+/// The secp256k1 secret key, signing without pink's chain extension.
+///
+/// The key is zeroed when dropped.
+#[derive(Clone)]
+pub struct SecretKey {
+    key: SigningKey,
+    address: Address,
+}
+
+impl SecretKey {
+    /// Creates a key from its 32 bytes, failing if they're zero or not below the curve order.
+    pub fn from_slice(bytes: &[u8]) -> Result<Self, SigningError> {
+        let bytes: [u8; 32] = bytes.try_into().map_err(|_| SigningError::InvalidKey)?;
+        let key = SigningKey::from_bytes(&bytes.into()).map_err(|_| SigningError::InvalidKey)?;
+        let public = key.verifying_key().to_encoded_point(false);
+        let hash = keccak256(&public.as_bytes()[1..]);
+        Ok(Self {
+            key,
+            address: Address::from_slice(&hash[12..]),
+        })
+    }
+
+    /// Exports the private key
+    pub fn private_key(&self) -> [u8; 32] {
+        self.key.to_bytes().into()
+    }
+
+    fn sign_recoverable(&self, message: &[u8; 32]) -> Result<(u64, H256, H256), SigningError> {
+        let (signature, recovery_id) = self
+            .key
+            .sign_prehash_recoverable(message)
+            .map_err(|_| SigningError::InvalidMessage)?;
+        let r = H256::from_slice(&signature.r().to_bytes());
+        let s = H256::from_slice(&signature.s().to_bytes());
+        Ok((recovery_id.to_byte().into(), r, s))
+    }
+}
+
+impl core::fmt::Debug for SecretKey {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_struct("SecretKey")
+            .field("address", &self.address)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Key for SecretKey {
+    fn sign(&self, message: &[u8; 32], chain_id: Option<u64>) -> Result<Signature, SigningError> {
+        let (standard_v, r, s) = self.sign_recoverable(message)?;
+        let v = if let Some(chain_id) = chain_id {
+            // When signing with a chain ID, add chain replay protection.
+            standard_v + 35 + chain_id * 2
+        } else {
+            // Otherwise, convert to 'Electrum' notation.
+            standard_v + 27
+        };
+
+        Ok(Signature { v, r, s })
+    }
+
+    fn sign_message(&self, message: &[u8; 32]) -> Result<Signature, SigningError> {
+        let (v, r, s) = self.sign_recoverable(message)?;
+
+        Ok(Signature { v, r, s })
+    }
+
+    fn address(&self) -> Address {
+        self.address
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+
+    #[test]
+    fn derives_address_and_rejects_invalid_keys() {
+        let key = SecretKey::from_slice(&hex!(
+            "4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318"
+        ))
+        .unwrap();
+        assert_eq!(key.address(), hex!("2c7536e3605d9c16a7a3d7b1898e529396a65c23").into());
+        assert_eq!(
+            key.private_key(),
+            hex!("4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318")
+        );
+
+        assert!(matches!(SecretKey::from_slice(&[0; 32]), Err(SigningError::InvalidKey)));
+        assert!(matches!(
+            SecretKey::from_slice(&[0xff; 32]),
+            Err(SigningError::InvalidKey)
+        ));
+        assert!(matches!(SecretKey::from_slice(&[1; 31]), Err(SigningError::InvalidKey)));
+    }
+}
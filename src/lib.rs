@@ -36,16 +36,30 @@ mod prelude {
 
 use prelude::*;
 
+#[cfg(all(feature = "signing", not(any(feature = "k256", feature = "ink_env", test))))]
+compile_error!("the `signing` feature needs a signature backend, enable `k256` or `ink_env`");
+
 /// Re-export of the `futures` crate.
 #[macro_use]
 pub extern crate futures;
 
 pub mod api;
+#[cfg(any(feature = "k256", feature = "ink_env", test))]
+mod backend;
 pub mod confirm;
 pub mod contract;
 pub mod error;
+pub mod fees;
 pub mod keys;
+pub mod logs;
+pub mod nonce;
+#[cfg(any(feature = "k256", feature = "ink_env", test))]
+pub mod recovery;
+#[cfg(feature = "signing")]
+pub mod signer;
 pub mod signing;
+pub mod sleeper;
+pub mod tracker;
 pub mod transports;
 pub mod types;
 
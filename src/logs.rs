@@ -0,0 +1,647 @@
+//! Fetching logs over wide block ranges.
+//!
+//! Providers usually reject `eth_getLogs` queries spanning too many blocks or returning too many
+//! results. The functions in this module split the requested range into chunks, shrinking the chunk
+//! size when the provider complains and growing it back after successful requests.
+//!
+//! [`LogScanner`] builds on top of it to follow the chain incrementally, keeping its position in a
+//! serializable [`ScanCursor`] and rolling back the logs of reorged blocks.
+
+use crate::prelude::*;
+use crate::{
+    api::Eth,
+    error::{self, Error, ErrorKind},
+    types::{BlockId, BlockNumber, Filter, Log, H256},
+    Transport,
+};
+use core::num::NonZeroUsize;
+use serde::{Deserialize, Serialize};
+
+/// Default maximum number of blocks queried in a single `eth_getLogs` request.
+pub const DEFAULT_MAX_SPAN: u64 = 2_000;
+
+/// Fragments of the error messages providers return when a log query is too wide.
+const RANGE_ERRORS: &[&str] = &[
+    "more than 10000 results",
+    "query returned more than",
+    "block range too large",
+    "block range is too large",
+    "block range exceeds",
+    "exceed maximum block range",
+    "range too large",
+    "response size exceeded",
+    "response size should not",
+    "query timeout exceeded",
+];
+
+/// Returns true if the error means the log query should be retried with a smaller block range.
+///
+/// Rate limiting is never a range error, even when the provider reports it with the same code.
+pub fn is_range_error(error: &Error) -> bool {
+    if error.kind() == Some(ErrorKind::RateLimited) {
+        return false;
+    }
+    match *error {
+        Error::Rpc(ref message) => {
+            let message = message.to_lowercase();
+            RANGE_ERRORS.iter().any(|fragment| message.contains(fragment))
+        }
+        _ => false,
+    }
+}
+
+/// Progress of a paginated log query.
+///
+/// The cursor only advances past a chunk once its logs were returned, so it can be persisted
+/// and used to resume the query later.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogsCursor {
+    /// First block that still has to be queried.
+    pub next_block: u64,
+    /// Last block of the range (inclusive).
+    pub to_block: u64,
+    /// Number of blocks queried by the next request.
+    pub span: u64,
+    /// Upper bound for `span`.
+    pub max_span: u64,
+}
+
+impl LogsCursor {
+    /// Creates a cursor covering `from_block..=to_block`.
+    pub fn new(from_block: u64, to_block: u64) -> Self {
+        LogsCursor {
+            next_block: from_block,
+            to_block,
+            span: DEFAULT_MAX_SPAN,
+            max_span: DEFAULT_MAX_SPAN,
+        }
+    }
+
+    /// Sets the maximum number of blocks queried in a single request.
+    pub fn max_span(mut self, max_span: u64) -> Self {
+        self.max_span = max_span.max(1);
+        self.span = self.max_span;
+        self
+    }
+
+    /// Returns true once the whole range has been fetched.
+    pub fn is_done(&self) -> bool {
+        self.next_block > self.to_block
+    }
+
+    fn chunk_end(&self) -> u64 {
+        // a deserialized cursor may have a zero span
+        let span = self.span.max(1);
+        self.next_block.saturating_add(span - 1).min(self.to_block)
+    }
+}
+
+/// Fetches the logs of the next chunk of the cursor range and advances the cursor.
+///
+/// The block range of `filter` is ignored, the one of the cursor is used instead.
+/// Returns `None` once the whole range has been fetched. Range errors are retried with
+/// a halved span until a single block is queried; any other error is returned as-is and
+/// leaves the cursor untouched.
+pub async fn next_logs<T: Transport>(
+    eth: &Eth<T>,
+    filter: &Filter,
+    cursor: &mut LogsCursor,
+) -> error::Result<Option<Vec<Log>>> {
+    if cursor.is_done() {
+        return Ok(None);
+    }
+    loop {
+        let end = cursor.chunk_end();
+        match eth.logs(filter.with_block_range(cursor.next_block, end)).await {
+            Ok(logs) => {
+                cursor.next_block = end + 1;
+                cursor.span = cursor.span.saturating_mul(2).min(cursor.max_span);
+                return Ok(Some(logs));
+            }
+            Err(ref err) if end > cursor.next_block && is_range_error(err) => {
+                let len = end - cursor.next_block + 1;
+                cursor.span = len / 2;
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+/// Fetches all the logs matching `filter` in the cursor range, in block order.
+///
+/// Stops after `max_requests` successful chunks if given, so the query can be resumed later from `cursor`.
+pub async fn fetch_logs<T: Transport>(
+    eth: &Eth<T>,
+    filter: &Filter,
+    cursor: &mut LogsCursor,
+    max_requests: Option<usize>,
+) -> error::Result<Vec<Log>> {
+    let mut result = Vec::new();
+    let mut requests = 0;
+    while max_requests != Some(requests) {
+        match next_logs(eth, filter, cursor).await? {
+            Some(logs) => result.extend(logs),
+            None => break,
+        }
+        requests += 1;
+    }
+    Ok(result)
+}
+
+/// Fetches all the logs matching `filter`, whose block range must be given as block numbers.
+pub async fn logs_in_range<T: Transport>(eth: &Eth<T>, filter: &Filter, max_span: u64) -> error::Result<Vec<Log>> {
+    let (from, to) = filter
+        .block_range()
+        .ok_or_else(|| Error::Decoder("the filter block range must be given as block numbers".into()))?;
+    let mut cursor = LogsCursor::new(from, to).max_span(max_span);
+    fetch_logs(eth, filter, &mut cursor, None).await
+}
+
+/// Default number of recent block hashes kept by a [`ScanCursor`].
+pub const DEFAULT_REORG_DEPTH: usize = 64;
+
+/// Number and hash of a processed block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockRef {
+    /// Block number
+    pub number: u64,
+    /// Block hash
+    pub hash: H256,
+}
+
+/// Position of a [`LogScanner`], meant to be persisted between invocations.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScanCursor {
+    /// First block that hasn't been scanned yet.
+    pub next_block: u64,
+    /// Recently processed blocks, oldest first. The last entry is the last processed block.
+    pub recent: Vec<BlockRef>,
+    /// Maximum number of entries kept in `recent`, i.e. the deepest reorg that can be rolled back precisely.
+    pub depth: NonZeroUsize,
+}
+
+impl ScanCursor {
+    /// Creates a cursor starting the scan at `from_block`.
+    pub fn new(from_block: u64) -> Self {
+        ScanCursor {
+            next_block: from_block,
+            recent: Vec::new(),
+            depth: NonZeroUsize::new(DEFAULT_REORG_DEPTH).expect("default depth is non-zero; qed"),
+        }
+    }
+
+    /// Sets the number of recent block hashes to keep.
+    pub fn depth(mut self, depth: NonZeroUsize) -> Self {
+        self.depth = depth;
+        self
+    }
+
+    /// Returns the last processed block.
+    pub fn last_block(&self) -> Option<&BlockRef> {
+        self.recent.last()
+    }
+
+    pub(crate) fn record(&mut self, block: BlockRef) {
+        match self.recent.last() {
+            Some(last) if last.number >= block.number => {}
+            _ => self.recent.push(block),
+        }
+    }
+
+    pub(crate) fn trim(&mut self) {
+        if self.recent.len() > self.depth.get() {
+            let excess = self.recent.len() - self.depth.get();
+            self.recent.drain(..excess);
+        }
+    }
+}
+
+/// Event returned by [`LogScanner::scan`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScanEvent {
+    /// A new log.
+    Log(Log),
+    /// A log flagged as removed by the provider.
+    Removed(Log),
+    /// A reorg orphaned every block after `after_block`; logs received for them must be discarded.
+    Rollback {
+        /// Last block still on the canonical chain.
+        after_block: u64,
+    },
+}
+
+/// Incremental, reorg-aware log scanner.
+///
+/// Each call to [`scan`](LogScanner::scan) returns the logs of the blocks mined since the previous
+/// call. Reorgs are detected by checking that the parent hash of the next block matches the last
+/// processed one; the cursor is then rewound to the most recent block still on the canonical chain.
+#[derive(Debug, Clone)]
+pub struct LogScanner<T: Transport> {
+    eth: Eth<T>,
+    filter: Filter,
+    max_blocks: u64,
+    max_span: u64,
+}
+
+impl<T: Transport> LogScanner<T> {
+    /// Creates a scanner for logs matching `filter`. The block range of the filter is ignored.
+    pub fn new(eth: Eth<T>, filter: Filter) -> Self {
+        LogScanner {
+            eth,
+            filter,
+            max_blocks: u64::MAX,
+            max_span: DEFAULT_MAX_SPAN,
+        }
+    }
+
+    /// Limits the number of blocks scanned by a single call.
+    pub fn max_blocks(mut self, max_blocks: u64) -> Self {
+        self.max_blocks = max_blocks.max(1);
+        self
+    }
+
+    /// Sets the maximum number of blocks queried by a single `eth_getLogs` request.
+    pub fn max_span(mut self, max_span: u64) -> Self {
+        self.max_span = max_span;
+        self
+    }
+
+    /// Scans the blocks mined since the last call and advances the cursor.
+    ///
+    /// The cursor is only updated if the whole scan succeeds.
+    pub async fn scan(&self, cursor: &mut ScanCursor) -> error::Result<Vec<ScanEvent>> {
+        let mut next = cursor.clone();
+        let mut events = Vec::new();
+
+        let head = self.eth.block_number().await?.as_u64();
+        if next.next_block > head {
+            // the head went back below the last processed block, which may have been reorged out
+            if matches!(next.last_block(), Some(last) if last.number > head) {
+                let after_block = self.rewind(&mut next).await?;
+                events.push(ScanEvent::Rollback { after_block });
+                *cursor = next;
+            }
+            return Ok(events);
+        }
+
+        if let Some(last) = next.last_block().copied() {
+            let parent_hash = match self.block_hash(next.next_block).await? {
+                Some((_, parent_hash)) => parent_hash,
+                None => return Ok(events),
+            };
+            if parent_hash != last.hash {
+                let after_block = self.rewind(&mut next).await?;
+                events.push(ScanEvent::Rollback { after_block });
+            }
+        }
+
+        let to_block = head.min(next.next_block.saturating_add(self.max_blocks - 1));
+        // read before the logs, so logs of a chain reorged in between don't match it
+        let (to_hash, _) = self
+            .block_hash(to_block)
+            .await?
+            .ok_or_else(|| Error::InvalidResponse(format!("block {} not found", to_block)))?;
+        let mut logs_cursor = LogsCursor::new(next.next_block, to_block).max_span(self.max_span);
+        let logs = fetch_logs(&self.eth, &self.filter, &mut logs_cursor, None).await?;
+
+        let mut previous: Option<BlockRef> = None;
+        for log in logs {
+            if let (Some(number), Some(hash)) = (log.block_number, log.block_hash) {
+                let number = number.as_u64();
+                let expected = match previous {
+                    _ if number == to_block => Some(to_hash),
+                    Some(block) if block.number == number => Some(block.hash),
+                    _ => None,
+                };
+                if matches!(expected, Some(expected) if expected != hash) {
+                    return Err(Error::InvalidResponse(format!(
+                        "block {} was reorganized during the scan",
+                        number
+                    )));
+                }
+                previous = Some(BlockRef { number, hash });
+                if number < to_block {
+                    next.record(BlockRef { number, hash });
+                }
+            }
+            if log.is_removed() {
+                events.push(ScanEvent::Removed(log));
+            } else {
+                events.push(ScanEvent::Log(log));
+            }
+        }
+        next.record(BlockRef {
+            number: to_block,
+            hash: to_hash,
+        });
+        next.trim();
+        next.next_block = to_block + 1;
+
+        *cursor = next;
+        Ok(events)
+    }
+
+    /// Rewinds the cursor to the most recent tracked block still on the canonical chain.
+    ///
+    /// If none of the tracked blocks is canonical anymore, rewinds to just before the oldest one.
+    async fn rewind(&self, cursor: &mut ScanCursor) -> error::Result<u64> {
+        while let Some(block) = cursor.recent.pop() {
+            if let Some((hash, _)) = self.block_hash(block.number).await? {
+                if hash == block.hash {
+                    cursor.recent.push(block);
+                    cursor.next_block = block.number + 1;
+                    return Ok(block.number);
+                }
+            }
+            cursor.next_block = block.number;
+        }
+        Ok(cursor.next_block.saturating_sub(1))
+    }
+
+    async fn block_hash(&self, number: u64) -> error::Result<Option<(H256, H256)>> {
+        let block = self
+            .eth
+            .block(BlockId::Number(BlockNumber::Number(number.into())))
+            .await?;
+        Ok(block.and_then(|block| block.hash.map(|hash| (hash, block.parent_hash))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        api::Namespace,
+        rpc::Value,
+        transports::test::{block_response, TestTransport},
+        types::{BlockNumber, FilterBuilder, H256},
+    };
+    use futures::executor::block_on;
+
+    const TOO_MANY: &str = "query returned more than 10000 results";
+
+    fn log(block: u64) -> Value {
+        serde_json::json!({
+            "address": "0x0000000000000000000000000000000000000123",
+            "topics": [],
+            "data": "0x",
+            "blockNumber": format!("{:#x}", block),
+            "blockHash": H256::from_low_u64_be(block),
+            "logIndex": "0x0",
+        })
+    }
+
+    fn block_request(number: &str) -> [String; 2] {
+        [format!(r#""{}""#, number), "false".into()]
+    }
+
+    fn range(from: &str, to: &str) -> [String; 1] {
+        [format!(r#"{{"fromBlock":"{}","toBlock":"{}"}}"#, from, to)]
+    }
+
+    #[test]
+    fn splits_range_on_errors() {
+        let mut transport = TestTransport::default();
+        transport.add_error(-32005, TOO_MANY);
+        transport.add_response(Value::Array(vec![log(0x10)]));
+        transport.add_response(Value::Array(vec![log(0x1a)]));
+        let eth = Eth::new(&transport);
+
+        let filter = FilterBuilder::default()
+            .from_block(BlockNumber::Number(0x10.into()))
+            .to_block(BlockNumber::Number(0x1f.into()))
+            .build();
+        let logs = block_on(logs_in_range(&eth, &filter, 16)).unwrap();
+
+        assert_eq!(
+            logs.iter()
+                .map(|log| log.block_number.unwrap().as_u64())
+                .collect::<Vec<_>>(),
+            vec![0x10, 0x1a]
+        );
+        transport.assert_request("eth_getLogs", &range("0x10", "0x1f"));
+        transport.assert_request("eth_getLogs", &range("0x10", "0x17"));
+        transport.assert_request("eth_getLogs", &range("0x18", "0x1f"));
+        transport.assert_no_more_requests();
+    }
+
+    #[test]
+    fn passes_rate_limit_through() {
+        let mut transport = TestTransport::default();
+        transport.add_error(-32005, "daily request count exceeded, request rate limited");
+        let eth = Eth::new(&transport);
+
+        let mut cursor = LogsCursor::new(0x10, 0x1f);
+        let err = block_on(next_logs(&eth, &Filter::default(), &mut cursor)).unwrap_err();
+        assert!(!is_range_error(&err));
+        assert_eq!(cursor, LogsCursor::new(0x10, 0x1f));
+        transport.assert_request("eth_getLogs", &range("0x10", "0x1f"));
+        transport.assert_no_more_requests();
+    }
+
+    #[test]
+    fn resumes_from_cursor() {
+        let mut transport = TestTransport::default();
+        transport.add_response(Value::Array(vec![log(1)]));
+        transport.add_error(-32000, "header not found");
+        let eth = Eth::new(transport.clone());
+
+        let mut cursor = LogsCursor::new(0, 9).max_span(5);
+        let filter = Filter::default();
+        let logs = block_on(fetch_logs(&eth, &filter, &mut cursor, Some(1))).unwrap();
+        assert_eq!(logs.len(), 1);
+        assert_eq!(cursor.next_block, 5);
+
+        let err = block_on(fetch_logs(&eth, &filter, &mut cursor, None)).unwrap_err();
+        assert!(!is_range_error(&err));
+        assert_eq!(cursor.next_block, 5);
+
+        let mut cursor: LogsCursor = serde_json::from_value(serde_json::to_value(&cursor).unwrap()).unwrap();
+        transport.add_response(Value::Array(vec![]));
+        block_on(fetch_logs(&eth, &filter, &mut cursor, None)).unwrap();
+        assert!(cursor.is_done());
+
+        transport.assert_request("eth_getLogs", &range("0x0", "0x4"));
+        transport.assert_request("eth_getLogs", &range("0x5", "0x9"));
+        transport.assert_request("eth_getLogs", &range("0x5", "0x9"));
+        transport.assert_no_more_requests();
+    }
+
+    #[test]
+    fn queries_one_block_with_zero_span() {
+        let mut transport = TestTransport::default();
+        transport.add_response(Value::Array(vec![]));
+        let eth = Eth::new(&transport);
+
+        let mut cursor: LogsCursor = serde_json::from_value(serde_json::json!({
+            "next_block": 5,
+            "to_block": 9,
+            "span": 0,
+            "max_span": 0,
+        }))
+        .unwrap();
+        block_on(next_logs(&eth, &Filter::default(), &mut cursor)).unwrap();
+        assert_eq!(cursor.next_block, 6);
+        transport.assert_request("eth_getLogs", &range("0x5", "0x5"));
+        transport.assert_no_more_requests();
+    }
+
+    #[test]
+    fn gives_up_on_single_block() {
+        let mut transport = TestTransport::default();
+        transport.add_error(-32005, TOO_MANY);
+        let eth = Eth::new(&transport);
+
+        let mut cursor = LogsCursor::new(7, 7);
+        let err = block_on(next_logs(&eth, &Filter::default(), &mut cursor)).unwrap_err();
+        assert!(is_range_error(&err));
+        assert_eq!(cursor.next_block, 7);
+    }
+
+    #[test]
+    fn scans_new_blocks() {
+        let mut transport = TestTransport::default();
+        transport.add_response(Value::String("0xc".into()));
+        transport.add_response(block_response(12, H256::from_low_u64_be(12), H256::from_low_u64_be(11)));
+        transport.add_response(Value::Array(vec![log(11)]));
+        let scanner = LogScanner::new(Eth::new(transport.clone()), Filter::default());
+
+        let mut cursor = ScanCursor::new(10);
+        let events = block_on(scanner.scan(&mut cursor)).unwrap();
+
+        assert_eq!(events.len(), 1);
+        assert!(matches!(events[0], ScanEvent::Log(ref log) if log.block_number == Some(11.into())));
+        assert_eq!(cursor.next_block, 13);
+        assert_eq!(
+            cursor.recent,
+            vec![
+                BlockRef {
+                    number: 11,
+                    hash: H256::from_low_u64_be(11)
+                },
+                BlockRef {
+                    number: 12,
+                    hash: H256::from_low_u64_be(12)
+                },
+            ]
+        );
+        transport.assert_request("eth_blockNumber", &[]);
+        transport.assert_request("eth_getBlockByNumber", &block_request("0xc"));
+        transport.assert_request("eth_getLogs", &range("0xa", "0xc"));
+        transport.assert_no_more_requests();
+    }
+
+    #[test]
+    fn rejects_logs_of_reorged_blocks() {
+        let mut transport = TestTransport::default();
+        transport.add_response(Value::String("0xc".into()));
+        transport.add_response(block_response(
+            12,
+            H256::from_low_u64_be(0x10c),
+            H256::from_low_u64_be(11),
+        ));
+        transport.add_response(Value::Array(vec![log(11), log(12)]));
+        let scanner = LogScanner::new(Eth::new(transport.clone()), Filter::default());
+
+        let mut cursor = ScanCursor::new(10);
+        let err = block_on(scanner.scan(&mut cursor)).unwrap_err();
+
+        assert_eq!(
+            err,
+            Error::InvalidResponse("block 12 was reorganized during the scan".into())
+        );
+        assert_eq!(cursor, ScanCursor::new(10));
+        transport.assert_request("eth_blockNumber", &[]);
+        transport.assert_request("eth_getBlockByNumber", &block_request("0xc"));
+        transport.assert_request("eth_getLogs", &range("0xa", "0xc"));
+        transport.assert_no_more_requests();
+    }
+
+    #[test]
+    fn rolls_back_on_reorg() {
+        let fork = |number: u64| H256::from_low_u64_be(0x100 + number);
+        let mut transport = TestTransport::default();
+        transport.add_response(Value::String("0xd".into()));
+        transport.add_response(block_response(12, fork(12), fork(11)));
+        transport.add_response(block_response(11, fork(11), H256::from_low_u64_be(10)));
+        transport.add_response(block_response(10, H256::from_low_u64_be(10), H256::from_low_u64_be(9)));
+        transport.add_response(block_response(13, fork(13), fork(12)));
+        transport.add_response(Value::Array(vec![]));
+        let scanner = LogScanner::new(Eth::new(transport.clone()), Filter::default());
+
+        let mut cursor = ScanCursor {
+            next_block: 12,
+            recent: vec![
+                BlockRef {
+                    number: 10,
+                    hash: H256::from_low_u64_be(10),
+                },
+                BlockRef {
+                    number: 11,
+                    hash: H256::from_low_u64_be(11),
+                },
+            ],
+            depth: NonZeroUsize::new(2).unwrap(),
+        };
+        let events = block_on(scanner.scan(&mut cursor)).unwrap();
+
+        assert_eq!(events, vec![ScanEvent::Rollback { after_block: 10 }]);
+        assert_eq!(cursor.next_block, 14);
+        assert_eq!(
+            cursor.recent,
+            vec![
+                BlockRef {
+                    number: 10,
+                    hash: H256::from_low_u64_be(10)
+                },
+                BlockRef {
+                    number: 13,
+                    hash: fork(13)
+                },
+            ]
+        );
+        transport.assert_request("eth_blockNumber", &[]);
+        transport.assert_request("eth_getBlockByNumber", &block_request("0xc"));
+        transport.assert_request("eth_getBlockByNumber", &block_request("0xb"));
+        transport.assert_request("eth_getBlockByNumber", &block_request("0xa"));
+        transport.assert_request("eth_getBlockByNumber", &block_request("0xd"));
+        transport.assert_request("eth_getLogs", &range("0xb", "0xd"));
+        transport.assert_no_more_requests();
+    }
+
+    #[test]
+    fn rolls_back_when_head_regresses() {
+        let mut transport = TestTransport::default();
+        transport.add_response(Value::String("0xc".into()));
+        transport.add_response(Value::Null);
+        transport.add_response(block_response(12, H256::from_low_u64_be(12), H256::from_low_u64_be(11)));
+        let scanner = LogScanner::new(Eth::new(transport.clone()), Filter::default());
+
+        let recent = (11..=13)
+            .map(|number| BlockRef {
+                number,
+                hash: H256::from_low_u64_be(number),
+            })
+            .collect::<Vec<_>>();
+        let mut cursor = ScanCursor {
+            next_block: 14,
+            recent: recent.clone(),
+            depth: NonZeroUsize::new(4).unwrap(),
+        };
+        let events = block_on(scanner.scan(&mut cursor)).unwrap();
+
+        assert_eq!(events, vec![ScanEvent::Rollback { after_block: 12 }]);
+        assert_eq!(cursor.next_block, 13);
+        assert_eq!(cursor.recent, recent[..2]);
+        transport.assert_request("eth_blockNumber", &[]);
+        transport.assert_request("eth_getBlockByNumber", &block_request("0xd"));
+        transport.assert_request("eth_getBlockByNumber", &block_request("0xc"));
+        transport.assert_no_more_requests();
+    }
+
+    #[test]
+    fn rejects_zero_depth() {
+        let cursor = serde_json::json!({ "next_block": 1, "recent": [], "depth": 0 });
+        assert!(serde_json::from_value::<ScanCursor>(cursor).is_err());
+    }
+}
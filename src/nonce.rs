@@ -0,0 +1,152 @@
+//! Local nonce allocation for signers sending several transactions.
+//!
+//! [`Accounts::sign_transaction`](crate::api::Accounts::sign_transaction) asks the node for the
+//! nonce of every transaction, so transactions signed before the previous one reached the node
+//! get the same nonce. A [`NonceManager`] hands out consecutive nonces instead, and can be kept in
+//! contract storage to keep doing so across pink invocations.
+
+use crate::{
+    api::Eth,
+    error,
+    types::{Address, BlockNumber, U256},
+    Transport,
+};
+use alloc::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+
+/// Nonces allocated locally but unknown to the node, `from..to`.
+///
+/// They were never sent or were dropped from the pool, so transactions with a higher nonce are
+/// stuck until the gap is filled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NonceGap {
+    /// First missing nonce, the node's pending transaction count.
+    pub from: U256,
+    /// Next locally allocated nonce.
+    pub to: U256,
+}
+
+/// Serializable allocator of transaction nonces, per sender.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NonceManager {
+    next: BTreeMap<Address, U256>,
+}
+
+impl NonceManager {
+    /// Creates a manager without any allocated nonce.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allocates the next nonce of `address`.
+    ///
+    /// The node's `pending` transaction count is used when it is ahead of the local state, e.g.
+    /// for the first allocation or when transactions were sent by another signer.
+    pub async fn next<T: Transport>(&mut self, eth: &Eth<T>, address: Address) -> error::Result<U256> {
+        let pending = eth.transaction_count(address, Some(BlockNumber::Pending)).await?;
+        let next = self.next.entry(address).or_insert(pending);
+        let nonce = (*next).max(pending);
+        *next = nonce + 1;
+        Ok(nonce)
+    }
+
+    /// Returns the nonce the next allocation for `address` will use at least.
+    pub fn peek(&self, address: Address) -> Option<U256> {
+        self.next.get(&address).copied()
+    }
+
+    /// Makes `nonce` and the ones after it available again, e.g. after a failed send.
+    pub fn rewind(&mut self, address: Address, nonce: U256) {
+        if let Some(next) = self.next.get_mut(&address) {
+            if nonce < *next {
+                *next = nonce;
+            }
+        }
+    }
+
+    /// Forgets the local state of `address`; the next allocation will use the node's count.
+    pub fn reset(&mut self, address: Address) {
+        self.next.remove(&address);
+    }
+
+    /// Checks whether nonces allocated for `address` are missing from the node.
+    ///
+    /// A gap can be filled by rewinding to [`NonceGap::from`] and resending, or by cancelling the
+    /// missing nonces.
+    pub async fn gap<T: Transport>(&self, eth: &Eth<T>, address: Address) -> error::Result<Option<NonceGap>> {
+        let next = match self.peek(address) {
+            Some(next) => next,
+            None => return Ok(None),
+        };
+        let pending = eth.transaction_count(address, Some(BlockNumber::Pending)).await?;
+        Ok((pending < next).then_some(NonceGap {
+            from: pending,
+            to: next,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{api::Namespace, rpc::Value, transports::test::TestTransport};
+    use futures::executor::block_on;
+
+    fn address() -> Address {
+        Address::from_low_u64_be(0x123)
+    }
+
+    #[test]
+    fn allocates_consecutive_nonces() {
+        let mut transport = TestTransport::default();
+        let eth = Eth::new(transport.clone());
+        let mut nonces = NonceManager::new();
+
+        transport.add_response(Value::String("0x5".into()));
+        transport.add_response(Value::String("0x5".into()));
+        assert_eq!(block_on(nonces.next(&eth, address())), Ok(5.into()));
+        assert_eq!(block_on(nonces.next(&eth, address())), Ok(6.into()));
+
+        // another signer used nonces 7 and 8
+        transport.add_response(Value::String("0x9".into()));
+        assert_eq!(block_on(nonces.next(&eth, address())), Ok(9.into()));
+
+        transport.assert_request(
+            "eth_getTransactionCount",
+            &[
+                r#""0x0000000000000000000000000000000000000123""#.into(),
+                r#""pending""#.into(),
+            ],
+        );
+    }
+
+    #[test]
+    fn detects_gap_and_rewinds() {
+        let mut transport = TestTransport::default();
+        let eth = Eth::new(transport.clone());
+        let mut nonces = NonceManager::new();
+        assert_eq!(block_on(nonces.gap(&eth, address())), Ok(None));
+
+        transport.add_response(Value::String("0x5".into()));
+        transport.add_response(Value::String("0x5".into()));
+        block_on(nonces.next(&eth, address())).unwrap();
+        block_on(nonces.next(&eth, address())).unwrap();
+
+        let mut nonces: NonceManager = serde_json::from_value(serde_json::to_value(&nonces).unwrap()).unwrap();
+        assert_eq!(nonces.peek(address()), Some(7.into()));
+
+        transport.add_response(Value::String("0x6".into()));
+        let gap = block_on(nonces.gap(&eth, address())).unwrap();
+        assert_eq!(
+            gap,
+            Some(NonceGap {
+                from: 6.into(),
+                to: 7.into()
+            })
+        );
+
+        nonces.rewind(address(), gap.unwrap().from);
+        transport.add_response(Value::String("0x6".into()));
+        assert_eq!(block_on(nonces.next(&eth, address())), Ok(6.into()));
+    }
+}
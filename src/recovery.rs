@@ -0,0 +1,131 @@
+//! Signature recovery and verification, portable between contracts and off-chain code.
+//!
+//! Inside contracts, signatures are recovered with `ink_env`. Elsewhere, the pure-Rust `k256`
+//! backend is used when enabled, so off-chain verifiers can share code with the contract.
+
+use crate::{
+    backend,
+    signing::{hash_message, RecoveryError, Signature},
+    types::{Address, H256, U256},
+};
+
+/// Half of the secp256k1 curve order, the highest `s` accepted by EIP-2.
+pub const SECP256K1_HALF_ORDER: U256 = U256([
+    0xdfe92f46681b20a0,
+    0x5d576e7357a4501d,
+    0xffffffffffffffff,
+    0x7fffffffffffffff,
+]);
+
+/// Recovery id of `v` given as the y-parity, in 'Electrum' notation or with EIP-155 replay
+/// protection.
+pub fn recovery_id(v: u64) -> Option<u8> {
+    match v {
+        0 | 1 => Some(v as u8),
+        27 | 28 => Some((v - 27) as u8),
+        v if v >= 35 => Some(((v - 35) % 2) as u8),
+        _ => None,
+    }
+}
+
+/// Checks that `s` is in the lower half of the curve order, as required by EIP-2.
+pub fn is_low_s(s: &H256) -> bool {
+    U256::from_big_endian(s.as_bytes()) <= SECP256K1_HALF_ORDER
+}
+
+/// Recovers the address of the key that signed `prehash`.
+///
+/// Like the `ecrecover` precompile, signatures with a high `s` are accepted.
+pub fn recover_address(prehash: &H256, signature: &[u8; 64], recovery_id: u8) -> Result<Address, RecoveryError> {
+    if recovery_id > 1 {
+        return Err(RecoveryError::InvalidSignature);
+    }
+    backend::recover_address(prehash.as_fixed_bytes(), signature, recovery_id)
+}
+
+/// Recovers the address of the key that signed `prehash`, with `v` in any notation accepted by
+/// `recovery_id`.
+pub fn recover(prehash: &H256, signature: &Signature) -> Result<Address, RecoveryError> {
+    let recovery_id = recovery_id(signature.v).ok_or(RecoveryError::InvalidSignature)?;
+    let mut rs = [0; 64];
+    rs[..32].copy_from_slice(signature.r.as_bytes());
+    rs[32..].copy_from_slice(signature.s.as_bytes());
+    recover_address(prehash, &rs, recovery_id)
+}
+
+/// Checks that `message`, enveloped as with `hash_message`, was signed by `address`.
+///
+/// Malleable signatures with a high `s` are rejected.
+pub fn verify(address: Address, message: &[u8], signature: &Signature) -> bool {
+    is_low_s(&signature.s) && recover(&hash_message(message), signature) == Ok(address)
+}
+
+/// Recovers the sender of a raw signed transaction, of any type supported by
+/// `DecodedTransaction::decode`.
+#[cfg(feature = "signing")]
+pub fn transaction_sender(raw: &[u8]) -> crate::error::Result<Address> {
+    let decoded = crate::types::DecodedTransaction::decode(raw)?;
+    decoded.tx.from.ok_or(RecoveryError::InvalidSignature.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+
+    // signed by the key `4c0883a6…318` of web3.js' documentation
+    const ADDRESS: [u8; 20] = hex!("2c7536e3605d9c16a7a3d7b1898e529396a65c23");
+
+    fn signature(v: u64, s: [u8; 32]) -> Signature {
+        Signature {
+            v,
+            r: hex!("b91467e570a6466aa9e9876cbcd013baba02900b8979d43fe208a4a4f339f5fd").into(),
+            s: s.into(),
+        }
+    }
+
+    #[test]
+    fn parses_recovery_ids() {
+        assert_eq!(recovery_id(0), Some(0));
+        assert_eq!(recovery_id(28), Some(1));
+        assert_eq!(recovery_id(37), Some(0));
+        assert_eq!(recovery_id(38), Some(1));
+        assert_eq!(recovery_id(2), None);
+        assert_eq!(recovery_id(29), None);
+    }
+
+    #[test]
+    fn recovers_and_verifies_messages() {
+        let low_s = hex!("6007e74cd82e037b800186422fc2da167c747ef045e5d18a5f5d4300f8e1a029");
+        let high_s = hex!("9ff818b327d1fc847ffe79bdd03d25e83e3a5df66962ceb160751b8bd754a118");
+        let hash = hash_message("Some data");
+
+        for v in [1, 28, 38] {
+            assert_eq!(recover(&hash, &signature(v, low_s)), Ok(ADDRESS.into()));
+        }
+        assert!(verify(ADDRESS.into(), b"Some data", &signature(28, low_s)));
+        assert!(!verify(ADDRESS.into(), b"Other data", &signature(28, low_s)));
+        assert!(!verify(Address::zero(), b"Some data", &signature(28, low_s)));
+        assert_eq!(
+            recover(&hash, &signature(29, low_s)),
+            Err(RecoveryError::InvalidSignature)
+        );
+
+        // the malleated signature recovers the same address, but isn't accepted by `verify`
+        assert!(is_low_s(&low_s.into()) && !is_low_s(&high_s.into()));
+        assert_eq!(recover(&hash, &signature(27, high_s)), Ok(ADDRESS.into()));
+        assert!(!verify(ADDRESS.into(), b"Some data", &signature(27, high_s)));
+    }
+
+    #[test]
+    fn recovers_transaction_sender() {
+        let raw = hex!("f86a8086d55698372431831e848094f0109fc8df283027b6285cc889f5aa624eac1f55843b9aca008025a009ebb6ca057a0535d6186462bc0b465b561c94a295bdb0621fc19208ab149a9ca0440ffd775ce91a833ab410777204d5341a6f9fa91216a6f3ee2c051fea6a0428");
+        assert_eq!(transaction_sender(&raw), Ok(ADDRESS.into()));
+
+        let malleated = hex!("f86a8086d55698372431831e848094f0109fc8df283027b6285cc889f5aa624eac1f55843b9aca008026a009ebb6ca057a0535d6186462bc0b465b561c94a295bdb0621fc19208ab149a9ca0bbf00288a316e57cc54bef888dfb2acaa03f3d3d9d31f947d1a6596ce5cc3d19");
+        assert_eq!(
+            transaction_sender(&malleated),
+            Err(RecoveryError::InvalidSignature.into())
+        );
+    }
+}
@@ -0,0 +1,274 @@
+//! Asynchronous signers, for keys held outside of the contract.
+//!
+//! [`Key`] signs synchronously and is available locally. A [`Signer`] may instead wait for a
+//! remote service such as web3signer, clef or a custody API, and report its failures. Every
+//! `Key` is a `Signer`, and [`RemoteSigner`] delegates to a node's `eth_signTransaction` and
+//! `eth_sign`.
+
+use crate::prelude::*;
+use crate::{
+    api::{Eth, Namespace},
+    error::{self, Error},
+    recovery,
+    signing::{self, Key, RecoveryError, Signature},
+    types::{
+        Address, Bytes, DecodedTransaction, SignedData, SignedTransaction, TransactionRequest, UnsignedTransaction,
+        H256,
+    },
+    Transport,
+};
+use core::future::Future;
+
+/// Signer of transactions and messages, possibly remote.
+pub trait Signer {
+    /// Address of the signing account.
+    fn address(&self) -> Address;
+
+    /// Signs a transaction prepared for this signer's address, e.g. with
+    /// `Accounts::prepare_transaction`.
+    fn sign_transaction(&self, tx: UnsignedTransaction) -> impl Future<Output = error::Result<SignedTransaction>>;
+
+    /// Signs a message enveloped as with `hash_message`, with `v` in 'Electrum' notation.
+    fn sign_personal_message(&self, message: &[u8]) -> impl Future<Output = error::Result<SignedData>>;
+}
+
+impl<K: Key> Signer for K {
+    fn address(&self) -> Address {
+        Key::address(self)
+    }
+
+    fn sign_transaction(&self, tx: UnsignedTransaction) -> impl Future<Output = error::Result<SignedTransaction>> {
+        futures::future::ready(tx.parameters.sign(self))
+    }
+
+    fn sign_personal_message(&self, message: &[u8]) -> impl Future<Output = error::Result<SignedData>> {
+        futures::future::ready(signing::sign_data(message, self).map_err(Error::from))
+    }
+}
+
+/// Signer delegating to an account of a node or signing service.
+///
+/// The returned signatures are checked against the signer's address, and signed transactions
+/// against the requested payload; a mismatch fails with `RecoveryError::InvalidSignature`.
+#[derive(Debug, Clone)]
+pub struct RemoteSigner<T: Transport> {
+    transport: T,
+    address: Address,
+}
+
+impl<T: Transport> RemoteSigner<T> {
+    /// Creates a signer for `address`, an account of the node behind `transport`.
+    pub fn new(transport: T, address: Address) -> Self {
+        RemoteSigner { transport, address }
+    }
+}
+
+impl<T: Transport> Signer for RemoteSigner<T> {
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    /// Signs the transaction with `eth_signTransaction`.
+    ///
+    /// Only legacy, EIP-2930 and EIP-1559 transactions can be requested; the chain ID is the
+    /// node's own.
+    async fn sign_transaction(&self, tx: UnsignedTransaction) -> error::Result<SignedTransaction> {
+        let parameters = tx.parameters();
+        let tx_type = parameters.transaction_type.map_or(0, |t| t.as_u64());
+        let eip1559 = match tx_type {
+            0 | 1 => false,
+            2 => true,
+            _ => return Err(Error::Unsupported(format!("transaction type {}", tx_type))),
+        };
+        let request = TransactionRequest {
+            from: self.address,
+            to: parameters.to,
+            gas: parameters.gas,
+            gas_price: parameters.gas_price.filter(|_| !eip1559),
+            value: Some(parameters.value),
+            data: Some(parameters.data.clone()),
+            nonce: parameters.nonce,
+            condition: None,
+            transaction_type: parameters.transaction_type,
+            access_list: parameters.access_list.clone(),
+            max_fee_per_gas: parameters.max_fee_per_gas.or(parameters.gas_price).filter(|_| eip1559),
+            max_priority_fee_per_gas: parameters.max_priority_fee_per_gas.filter(|_| eip1559),
+        };
+
+        let raw = Eth::new(self.transport.clone()).sign_transaction(request).await?;
+        let decoded = DecodedTransaction::decode(&raw.raw.0)?.tx;
+        let (v, r, s) = match (decoded.v, decoded.r, decoded.s) {
+            (Some(v), Some(r), Some(s)) => (v, r, s),
+            _ => return Err(Error::Recovery(RecoveryError::InvalidSignature)),
+        };
+        let (mut r_bytes, mut s_bytes) = (H256::zero(), H256::zero());
+        r.to_big_endian(r_bytes.as_bytes_mut());
+        s.to_big_endian(s_bytes.as_bytes_mut());
+        tx.attach_signature(Signature {
+            v: v.as_u64(),
+            r: r_bytes,
+            s: s_bytes,
+        })
+    }
+
+    /// Signs the message with `eth_sign`.
+    async fn sign_personal_message(&self, message: &[u8]) -> error::Result<SignedData> {
+        let message = message.to_owned();
+        let signature = Eth::new(self.transport.clone())
+            .sign(self.address, Bytes(message.clone()))
+            .await?;
+        let signature = signature.as_bytes();
+        // some signers return the y-parity rather than 'Electrum' notation
+        let v = match signature[64] {
+            v @ (0 | 1) => v + 27,
+            v => v,
+        };
+        let mut bytes = signature.to_vec();
+        bytes[64] = v;
+
+        let signed = SignedData {
+            message_hash: signing::hash_message(&message),
+            message,
+            v,
+            r: H256::from_slice(&signature[..32]),
+            s: H256::from_slice(&signature[32..64]),
+            signature: Bytes(bytes),
+        };
+        let signature = Signature {
+            v: v.into(),
+            r: signed.r,
+            s: signed.s,
+        };
+        if !recovery::verify(self.address, &signed.message, &signature) {
+            return Err(Error::Recovery(RecoveryError::InvalidSignature));
+        }
+        Ok(signed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{api::Accounts, signing::TestKey, transports::test::TestTransport, types::TransactionParameters};
+    use futures::executor::block_on;
+    use hex_literal::hex;
+    use serde_json::json;
+
+    // signed by the key `4c0883a6…318` of web3.js' documentation
+    const ADDRESS: [u8; 20] = hex!("2c7536e3605d9c16a7a3d7b1898e529396a65c23");
+    const RAW_TX: &str = "0xf86a8086d55698372431831e848094f0109fc8df283027b6285cc889f5aa624eac1f55843b9aca008025a009ebb6ca057a0535d6186462bc0b465b561c94a295bdb0621fc19208ab149a9ca0440ffd775ce91a833ab410777204d5341a6f9fa91216a6f3ee2c051fea6a0428";
+    const SIGNATURE: &str = "0xb91467e570a6466aa9e9876cbcd013baba02900b8979d43fe208a4a4f339f5fd6007e74cd82e037b800186422fc2da167c747ef045e5d18a5f5d4300f8e1a0291c";
+
+    fn parameters() -> TransactionParameters {
+        TransactionParameters {
+            nonce: Some(0.into()),
+            to: Some(hex!("F0109fC8DF283027b6285cc889F5aA624EaC1F55").into()),
+            gas: Some(2_000_000.into()),
+            gas_price: Some(234_567_897_654_321u64.into()),
+            value: 1_000_000_000.into(),
+            chain_id: Some(1),
+            ..Default::default()
+        }
+    }
+
+    fn raw_transaction() -> serde_json::Value {
+        json!({
+            "raw": RAW_TX,
+            "tx": {
+                "hash": "0xd8f64a42b57be0d565f385378db2f6bf324ce14a594afc05de90436e9ce01f60",
+                "nonce": "0x0",
+                "blockHash": null,
+                "blockNumber": null,
+                "transactionIndex": null,
+                "from": "0x2c7536e3605d9c16a7a3d7b1898e529396a65c23",
+                "to": "0xf0109fc8df283027b6285cc889f5aa624eac1f55",
+                "value": "0x3b9aca00",
+                "gas": "0x1e8480",
+                "gasPrice": "0xd55698372431",
+                "input": "0x"
+            }
+        })
+    }
+
+    #[test]
+    fn keys_are_signers() {
+        let accounts = Accounts::new(TestTransport::default());
+        let signed = block_on(accounts.sign_transaction_with(parameters(), &TestKey));
+        assert_eq!(signed, parameters().sign(TestKey));
+
+        let signed = block_on(TestKey.sign_personal_message(b"Some data"));
+        assert_eq!(signed, Ok(accounts.sign("Some data", TestKey)));
+    }
+
+    #[test]
+    fn remote_signer_signs_transaction() {
+        let mut transport = TestTransport::default();
+        transport.add_response(raw_transaction());
+        let signer = RemoteSigner::new(transport.clone(), ADDRESS.into());
+
+        let unsigned = parameters().unsigned(ADDRESS.into()).unwrap();
+        let signed = block_on(signer.sign_transaction(unsigned)).unwrap();
+        assert_eq!(signed.raw_transaction.0, hex::decode(&RAW_TX[2..]).unwrap());
+        assert_eq!(signed.v, 0x25);
+
+        transport.assert_request(
+            "eth_signTransaction",
+            &[r#"{"from":"0x2c7536e3605d9c16a7a3d7b1898e529396a65c23","to":"0xf0109fc8df283027b6285cc889f5aa624eac1f55","gas":"0x1e8480","gasPrice":"0xd55698372431","value":"0x3b9aca00","data":"0x","nonce":"0x0"}"#.into()],
+        );
+        transport.assert_no_more_requests();
+
+        // the node signed a different payload than requested
+        transport.add_response(raw_transaction());
+        let unsigned = TransactionParameters {
+            nonce: Some(1.into()),
+            ..parameters()
+        }
+        .unsigned(ADDRESS.into())
+        .unwrap();
+        assert_eq!(
+            block_on(signer.sign_transaction(unsigned)),
+            Err(Error::Recovery(RecoveryError::InvalidSignature))
+        );
+
+        // blob transactions can't be requested with `eth_signTransaction`
+        let unsigned = TransactionParameters {
+            transaction_type: Some(3.into()),
+            max_priority_fee_per_gas: Some(1.into()),
+            max_fee_per_blob_gas: Some(1.into()),
+            blob_versioned_hashes: Some(vec![H256::from_low_u64_be(1)]),
+            ..parameters()
+        }
+        .unsigned(ADDRESS.into())
+        .unwrap();
+        assert_eq!(
+            block_on(signer.sign_transaction(unsigned)),
+            Err(Error::Unsupported("transaction type 3".into()))
+        );
+    }
+
+    #[test]
+    fn remote_signer_signs_message() {
+        let mut transport = TestTransport::default();
+        transport.add_response(json!(SIGNATURE));
+        let signer = RemoteSigner::new(transport.clone(), ADDRESS.into());
+
+        let signed = block_on(signer.sign_personal_message(b"Some data")).unwrap();
+        assert_eq!(signed.v, 28);
+        assert_eq!(signed.signature.0, hex::decode(&SIGNATURE[2..]).unwrap());
+        transport.assert_request(
+            "eth_sign",
+            &[
+                r#""0x2c7536e3605d9c16a7a3d7b1898e529396a65c23""#.into(),
+                r#""0x536f6d652064617461""#.into(),
+            ],
+        );
+
+        // another account signed
+        transport.add_response(json!(SIGNATURE));
+        let signer = RemoteSigner::new(transport.clone(), Address::from_low_u64_be(0x123));
+        assert_eq!(
+            block_on(signer.sign_personal_message(b"Some data")),
+            Err(Error::Recovery(RecoveryError::InvalidSignature))
+        );
+    }
+}
@@ -8,6 +8,9 @@ pub enum SigningError {
     /// A message to sign is invalid. Has to be a non-zero 32-bytes slice.
     #[display(fmt = "Message has to be a non-zero 32-bytes slice.")]
     InvalidMessage,
+    /// A secret key is invalid. Has to be a non-zero 32-bytes scalar below the curve order.
+    #[display(fmt = "Secret key has to be a non-zero 32-bytes scalar below the curve order.")]
+    InvalidKey,
 }
 #[cfg(feature = "std")]
 impl std::error::Error for SigningError {}
@@ -31,7 +34,8 @@ pub use feature_gated::*;
 #[cfg(feature = "signing")]
 mod feature_gated {
     use super::*;
-    use crate::types::Address;
+    use crate::types::{Address, Bytes, SignedData};
+    use core::convert::TryInto;
     /// A trait representing ethereum-compatible key with signing capabilities.
     ///
     /// The purpose of this trait is to prevent leaking `secp256k1::SecretKey` struct
@@ -43,8 +47,7 @@ mod feature_gated {
     /// - or https://crates.io/crates/zeroize
     /// if you care enough about your secrets to be used securely.
     ///
-    /// If it's enough to pass a reference to `SecretKey` (lifetimes) than you can use `SecretKeyRef`
-    /// wrapper.
+    /// If it's enough to pass a reference to a key (lifetimes), `&K` implements `Key` as well.
     pub trait Key {
         /// Sign given message and include chain-id replay protection.
         ///
@@ -60,6 +63,78 @@ mod feature_gated {
         /// Get public address that this key represents.
         fn address(&self) -> Address;
     }
+
+    impl<K: Key + ?Sized> Key for &K {
+        fn sign(&self, message: &[u8; 32], chain_id: Option<u64>) -> Result<Signature, SigningError> {
+            (**self).sign(message, chain_id)
+        }
+
+        fn sign_message(&self, message: &[u8; 32]) -> Result<Signature, SigningError> {
+            (**self).sign_message(message)
+        }
+
+        fn address(&self) -> Address {
+            (**self).address()
+        }
+    }
+
+    /// Signs `message` enveloped as with `hash_message`, with `v` in 'Electrum' notation.
+    pub(crate) fn sign_data(message: &[u8], key: &impl Key) -> Result<SignedData, SigningError> {
+        let message_hash = hash_message(message);
+        let signature = key.sign(&message_hash.0, None)?;
+        let v = signature
+            .v
+            .try_into()
+            .expect("signature recovery in electrum notation always fits in a u8");
+
+        let mut bytes = Vec::with_capacity(65);
+        bytes.extend_from_slice(signature.r.as_bytes());
+        bytes.extend_from_slice(signature.s.as_bytes());
+        bytes.push(v);
+
+        Ok(SignedData {
+            message: message.to_owned(),
+            message_hash,
+            v,
+            r: signature.r,
+            s: signature.s,
+            signature: Bytes(bytes),
+        })
+    }
+
+    /// Version of the versioned hashes of KZG commitments, from EIP-4844.
+    pub const VERSIONED_HASH_VERSION_KZG: u8 = 0x01;
+
+    /// Compute the versioned hash of a blob's KZG commitment, as listed in blob transactions.
+    pub fn blob_versioned_hash(commitment: &[u8]) -> H256 {
+        let mut hash = crate::backend::sha256(commitment);
+        hash[0] = VERSIONED_HASH_VERSION_KZG;
+        hash.into()
+    }
+
+    /// Key of address `0x...0123` returning a fixed signature, for tests not checking signatures.
+    #[cfg(test)]
+    #[derive(Debug, Clone, Copy)]
+    pub(crate) struct TestKey;
+
+    #[cfg(test)]
+    impl Key for TestKey {
+        fn sign(&self, message: &[u8; 32], _chain_id: Option<u64>) -> Result<Signature, SigningError> {
+            self.sign_message(message)
+        }
+
+        fn sign_message(&self, _message: &[u8; 32]) -> Result<Signature, SigningError> {
+            Ok(Signature {
+                v: 0,
+                r: H256::from_low_u64_be(1),
+                s: H256::from_low_u64_be(2),
+            })
+        }
+
+        fn address(&self) -> Address {
+            Address::from_low_u64_be(0x123)
+        }
+    }
 }
 
 /// A struct that represents the components of a secp256k1 signature.
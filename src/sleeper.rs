@@ -0,0 +1,213 @@
+//! Timers used between polls.
+//!
+//! Polling helpers (filter streams, confirmations, deployments) wait between two requests by
+//! calling a [`Sleeper`]. Pink contracts can't block, so they use [`StepSleeper`], which allows a
+//! fixed number of polls and then gives up with [`Error::NotYet`] so the caller can resume in a
+//! later query. Native executors can use [`ThreadSleeper`]. The sleeper used when none is given
+//! is [`DefaultSleeper`].
+//!
+//! The entry points predating sleepers, which only take a poll interval, keep polling again right
+//! away as they always did, i.e. with [`NoopSleeper`].
+
+use crate::error::{self, Error};
+use alloc::rc::Rc;
+use core::{cell::Cell, future::Future, time::Duration};
+
+/// Waits between two polls.
+pub trait Sleeper: Clone {
+    /// Future resolved once the caller may poll again.
+    type Sleep: Future<Output = error::Result<()>>;
+
+    /// Waits for `duration`. Returns an error if the caller should stop polling.
+    fn sleep(&self, duration: Duration) -> Self::Sleep;
+}
+
+/// Sleeper used when none is given: [`StepSleeper`], which never spins in a contract, or
+/// [`ThreadSleeper`] with `std` and without `pink`.
+#[cfg(not(all(feature = "std", not(feature = "pink"))))]
+pub type DefaultSleeper = StepSleeper;
+
+/// Sleeper used when none is given: [`StepSleeper`], which never spins in a contract, or
+/// [`ThreadSleeper`] with `std` and without `pink`.
+#[cfg(all(feature = "std", not(feature = "pink")))]
+pub type DefaultSleeper = ThreadSleeper;
+
+/// How a poller waits between two polls.
+#[derive(Debug, Clone)]
+pub struct PollOptions<S = DefaultSleeper> {
+    /// Time to wait between two polls.
+    pub interval: Duration,
+    /// Sleeper waiting `interval`.
+    pub sleeper: S,
+}
+
+impl PollOptions {
+    /// Polls every `interval` with the [`DefaultSleeper`].
+    pub fn new(interval: Duration) -> Self {
+        PollOptions {
+            interval,
+            sleeper: Default::default(),
+        }
+    }
+}
+
+impl<S: Sleeper> PollOptions<S> {
+    /// Sets the sleeper waiting between two polls.
+    pub fn sleeper<S2: Sleeper>(self, sleeper: S2) -> PollOptions<S2> {
+        PollOptions {
+            interval: self.interval,
+            sleeper,
+        }
+    }
+
+    /// Waits `interval` with the sleeper.
+    pub fn sleep(&self) -> S::Sleep {
+        self.sleeper.sleep(self.interval)
+    }
+}
+
+/// Sleeper returning immediately, polling as fast as the transport allows.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoopSleeper;
+
+impl Sleeper for NoopSleeper {
+    type Sleep = core::future::Ready<error::Result<()>>;
+
+    fn sleep(&self, _duration: Duration) -> Self::Sleep {
+        core::future::ready(Ok(()))
+    }
+}
+
+/// Sleeper allowing a fixed number of polls, then failing with [`Error::NotYet`].
+///
+/// Clones share the same budget. The default sleeper has no steps, so only the first poll is made.
+#[derive(Debug, Default, Clone)]
+pub struct StepSleeper {
+    remaining: Rc<Cell<usize>>,
+}
+
+impl StepSleeper {
+    /// Creates a sleeper allowing `steps` more polls.
+    pub fn new(steps: usize) -> Self {
+        StepSleeper {
+            remaining: Rc::new(Cell::new(steps)),
+        }
+    }
+
+    /// Returns the number of polls left.
+    pub fn remaining(&self) -> usize {
+        self.remaining.get()
+    }
+}
+
+impl Sleeper for StepSleeper {
+    type Sleep = core::future::Ready<error::Result<()>>;
+
+    fn sleep(&self, _duration: Duration) -> Self::Sleep {
+        let result = match self.remaining.get() {
+            0 => Err(Error::NotYet),
+            remaining => {
+                self.remaining.set(remaining - 1);
+                Ok(())
+            }
+        };
+        core::future::ready(result)
+    }
+}
+
+#[cfg(feature = "std")]
+pub use self::thread::{ThreadSleep, ThreadSleeper};
+
+#[cfg(feature = "std")]
+mod thread {
+    use super::Sleeper;
+    use crate::error;
+    use core::{
+        future::Future,
+        pin::Pin,
+        task::{Context, Poll, Waker},
+        time::Duration,
+    };
+    use std::sync::{Arc, Mutex};
+
+    /// Sleeper waiting for real time, for std executors.
+    ///
+    /// Every sleep spawns a thread, so it works with any executor but shouldn't be used in contracts.
+    #[derive(Debug, Default, Clone, Copy)]
+    pub struct ThreadSleeper;
+
+    impl Sleeper for ThreadSleeper {
+        type Sleep = ThreadSleep;
+
+        fn sleep(&self, duration: Duration) -> Self::Sleep {
+            ThreadSleep { duration, state: None }
+        }
+    }
+
+    #[derive(Debug, Default)]
+    struct SleepState {
+        done: bool,
+        waker: Option<Waker>,
+    }
+
+    /// Future returned by [`ThreadSleeper`].
+    #[derive(Debug)]
+    pub struct ThreadSleep {
+        duration: Duration,
+        state: Option<Arc<Mutex<SleepState>>>,
+    }
+
+    impl Future for ThreadSleep {
+        type Output = error::Result<()>;
+
+        fn poll(mut self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+            if self.duration.is_zero() {
+                return Poll::Ready(Ok(()));
+            }
+            let duration = self.duration;
+            let state = self.state.get_or_insert_with(|| {
+                let state = Arc::new(Mutex::new(SleepState::default()));
+                let thread_state = state.clone();
+                std::thread::spawn(move || {
+                    std::thread::sleep(duration);
+                    let mut state = thread_state.lock().expect("sleep state lock poisoned");
+                    state.done = true;
+                    if let Some(waker) = state.waker.take() {
+                        waker.wake();
+                    }
+                });
+                state
+            });
+            let mut state = state.lock().expect("sleep state lock poisoned");
+            if state.done {
+                Poll::Ready(Ok(()))
+            } else {
+                state.waker = Some(ctx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+
+    #[test]
+    fn step_sleeper_runs_out() {
+        let sleeper = StepSleeper::new(1);
+        let clone = sleeper.clone();
+
+        assert_eq!(block_on(sleeper.sleep(Duration::from_secs(1))), Ok(()));
+        assert_eq!(clone.remaining(), 0);
+        assert_eq!(block_on(clone.sleep(Duration::from_secs(1))), Err(Error::NotYet));
+    }
+
+    #[test]
+    fn thread_sleeper_waits() {
+        let start = std::time::Instant::now();
+        assert_eq!(block_on(ThreadSleeper.sleep(Duration::from_millis(20))), Ok(()));
+        assert!(start.elapsed() >= Duration::from_millis(20));
+    }
+}
@@ -0,0 +1,411 @@
+//! Tracking submitted transactions across invocations.
+//!
+//! A pink contract can't wait for a transaction to be mined within a single query. Instead, it can
+//! keep a serialized [`TxTracker`] in its storage and [`poll`](TxTracker::poll) it once per invocation.
+//! Trackers created from a signed transaction also keep its fees, so that a stuck transaction can
+//! be replaced with bumped fees in a later invocation.
+
+use crate::prelude::*;
+#[cfg(feature = "signing")]
+use crate::types::{DecodedTransaction, SignedTransaction, Transaction};
+use crate::{
+    api::Eth,
+    error,
+    logs::BlockRef,
+    types::{Address, BlockNumber, TransactionId, TransactionReceipt, H256, U256},
+    Transport,
+};
+use alloc::boxed::Box;
+use serde::{Deserialize, Serialize};
+
+/// Status of a tracked transaction, returned by [`TxTracker::poll`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum TxStatus {
+    /// Known to the node but not mined yet.
+    Pending,
+    /// Mined, with the given number of confirmations (1 when just mined), but not confirmed yet.
+    Mined(u64),
+    /// Mined with at least the required number of confirmations.
+    Confirmed(Box<TransactionReceipt>),
+    /// Unknown to the node a few blocks after submission, or its nonce was used by an untracked
+    /// transaction. It will never be mined.
+    Dropped,
+    /// A replacement transaction with the same nonce was mined instead.
+    Replaced(H256),
+    /// The block the transaction was mined in was reorged out. Confirmations start over.
+    Reorged,
+}
+
+/// Number of blocks after submission during which a transaction unknown to the node is still
+/// considered pending, e.g. while it propagates to the backend answering the poll.
+const PROPAGATION_BLOCKS: u64 = 2;
+
+/// Serializable state of a submitted transaction.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TxTracker {
+    /// Hash of the submitted transaction.
+    pub hash: H256,
+    /// Sender of the transaction.
+    pub sender: Address,
+    /// Nonce of the transaction.
+    pub nonce: U256,
+    /// Block number at submission time.
+    pub submitted_block: u64,
+    /// Number of confirmations required before the transaction is considered confirmed.
+    pub confirmations: u64,
+    /// Hashes of replacement transactions sharing the same nonce, oldest first.
+    pub replacements: Vec<H256>,
+    /// Block the transaction was last seen mined in.
+    pub mined_block: Option<BlockRef>,
+    /// Gas price of the most recently submitted legacy or EIP-2930 transaction, if known.
+    #[serde(default)]
+    pub gas_price: Option<U256>,
+    /// Max fee per gas of the most recently submitted EIP-1559 transaction, if known.
+    #[serde(default)]
+    pub max_fee_per_gas: Option<U256>,
+    /// Max priority fee per gas of the most recently submitted EIP-1559 transaction, if known.
+    #[serde(default)]
+    pub max_priority_fee_per_gas: Option<U256>,
+}
+
+impl TxTracker {
+    /// Starts tracking a transaction, requiring a single confirmation.
+    pub fn new(hash: H256, sender: Address, nonce: U256, submitted_block: u64) -> Self {
+        TxTracker {
+            hash,
+            sender,
+            nonce,
+            submitted_block,
+            confirmations: 1,
+            replacements: Vec::new(),
+            mined_block: None,
+            gas_price: None,
+            max_fee_per_gas: None,
+            max_priority_fee_per_gas: None,
+        }
+    }
+
+    /// Starts tracking a signed transaction, with its sender, nonce and fees taken from it.
+    #[cfg(feature = "signing")]
+    pub fn from_signed(signed: &SignedTransaction, submitted_block: u64) -> error::Result<Self> {
+        let tx = DecodedTransaction::decode(&signed.raw_transaction.0)?.tx;
+        let sender = tx.from.unwrap_or_default();
+        let mut tracker = TxTracker::new(signed.transaction_hash, sender, tx.nonce, submitted_block);
+        tracker.set_fees(&tx);
+        Ok(tracker)
+    }
+
+    /// Sets the number of required confirmations.
+    pub fn confirmations(mut self, confirmations: u64) -> Self {
+        self.confirmations = confirmations.max(1);
+        self
+    }
+
+    /// Records a replacement transaction sent with the same nonce.
+    ///
+    /// Its fees are unknown, so the tracked fees are cleared. Use `add_signed_replacement` to
+    /// keep them.
+    pub fn add_replacement(&mut self, hash: H256) {
+        self.replacements.push(hash);
+        self.gas_price = None;
+        self.max_fee_per_gas = None;
+        self.max_priority_fee_per_gas = None;
+    }
+
+    /// Records a signed replacement transaction and its fees, e.g. as returned by
+    /// `Accounts::sign_replacement`.
+    ///
+    /// The replacement must be sent by the tracked sender with the tracked nonce, otherwise
+    /// `Error::InvalidParameter` is returned.
+    #[cfg(feature = "signing")]
+    pub fn add_signed_replacement(&mut self, signed: &SignedTransaction) -> error::Result<()> {
+        let tx = DecodedTransaction::decode(&signed.raw_transaction.0)?.tx;
+        if tx.from != Some(self.sender) || tx.nonce != self.nonce {
+            return Err(error::Error::InvalidParameter("replacement"));
+        }
+        self.replacements.push(signed.transaction_hash);
+        self.set_fees(&tx);
+        Ok(())
+    }
+
+    #[cfg(feature = "signing")]
+    fn set_fees(&mut self, tx: &Transaction) {
+        self.gas_price = tx.gas_price;
+        self.max_fee_per_gas = tx.max_fee_per_gas;
+        self.max_priority_fee_per_gas = tx.max_priority_fee_per_gas;
+    }
+
+    /// Hash of the most recently submitted transaction.
+    pub fn latest_hash(&self) -> H256 {
+        self.replacements.last().copied().unwrap_or(self.hash)
+    }
+
+    /// Returns the first receipt found for the transaction or one of its replacements.
+    async fn find_receipt<T: Transport>(&self, eth: &Eth<T>) -> error::Result<Option<(H256, TransactionReceipt)>> {
+        for hash in core::iter::once(self.hash).chain(self.replacements.iter().copied()) {
+            if let Some(receipt) = eth.transaction_receipt(hash).await? {
+                return Ok(Some((hash, receipt)));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Checks the transaction once and updates the tracked state.
+    pub async fn poll<T: Transport>(&mut self, eth: &Eth<T>) -> error::Result<TxStatus> {
+        let (hash, receipt) = match self.find_receipt(eth).await? {
+            Some(found) => found,
+            None => {
+                if self.mined_block.take().is_some() {
+                    return Ok(TxStatus::Reorged);
+                }
+                let nonce = eth.transaction_count(self.sender, Some(BlockNumber::Latest)).await?;
+                if nonce <= self.nonce {
+                    if eth
+                        .transaction(TransactionId::Hash(self.latest_hash()))
+                        .await?
+                        .is_some()
+                    {
+                        return Ok(TxStatus::Pending);
+                    }
+                    let head = eth.block_number().await?.as_u64();
+                    if head <= self.submitted_block + PROPAGATION_BLOCKS {
+                        return Ok(TxStatus::Pending);
+                    }
+                    return Ok(TxStatus::Dropped);
+                }
+                // one of the transactions may have been mined since its receipt was checked
+                match self.find_receipt(eth).await? {
+                    Some(found) => found,
+                    None => return Ok(TxStatus::Dropped),
+                }
+            }
+        };
+
+        let block = match (receipt.block_number, receipt.block_hash) {
+            (Some(number), Some(hash)) => BlockRef {
+                number: number.as_u64(),
+                hash,
+            },
+            _ => return Ok(TxStatus::Pending),
+        };
+        let previous = self.mined_block.replace(block);
+        if matches!(previous, Some(previous) if previous != block) {
+            return Ok(TxStatus::Reorged);
+        }
+        if hash != self.hash {
+            return Ok(TxStatus::Replaced(hash));
+        }
+
+        let head = eth.block_number().await?.as_u64();
+        let confirmations = (head + 1).saturating_sub(block.number);
+        if confirmations >= self.confirmations {
+            Ok(TxStatus::Confirmed(Box::new(receipt)))
+        } else {
+            Ok(TxStatus::Mined(confirmations))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{api::Namespace, rpc::Value, transports::test::TestTransport};
+    use futures::executor::block_on;
+    use serde_json::json;
+
+    fn receipt(hash: H256, block: u64, block_hash: H256) -> Value {
+        json!(TransactionReceipt {
+            transaction_hash: hash,
+            block_number: Some(block.into()),
+            block_hash: Some(block_hash),
+            status: Some(1.into()),
+            ..Default::default()
+        })
+    }
+
+    fn tracker() -> TxTracker {
+        TxTracker::new(H256::from_low_u64_be(1), Address::from_low_u64_be(0x123), 5.into(), 10).confirmations(3)
+    }
+
+    #[test]
+    fn pending_then_mined_then_confirmed() {
+        let mut transport = TestTransport::default();
+        let eth = Eth::new(transport.clone());
+        let mut tracker = tracker();
+
+        transport.add_response(Value::Null);
+        transport.add_response(Value::String("0x5".into()));
+        transport.add_response(json!({
+            "hash": H256::from_low_u64_be(1),
+            "nonce": "0x5",
+            "blockHash": null,
+            "blockNumber": null,
+            "transactionIndex": null,
+            "from": Address::from_low_u64_be(0x123),
+            "to": null,
+            "value": "0x0",
+            "gas": "0x5208",
+            "gasPrice": "0x1",
+            "input": "0x"
+        }));
+        assert_eq!(block_on(tracker.poll(&eth)), Ok(TxStatus::Pending));
+
+        transport.add_response(receipt(H256::from_low_u64_be(1), 11, H256::from_low_u64_be(0xb)));
+        transport.add_response(Value::String("0xc".into()));
+        assert_eq!(block_on(tracker.poll(&eth)), Ok(TxStatus::Mined(2)));
+
+        let mut tracker: TxTracker = serde_json::from_value(serde_json::to_value(&tracker).unwrap()).unwrap();
+        transport.add_response(receipt(H256::from_low_u64_be(1), 11, H256::from_low_u64_be(0xb)));
+        transport.add_response(Value::String("0xd".into()));
+        assert!(matches!(block_on(tracker.poll(&eth)), Ok(TxStatus::Confirmed(_))));
+
+        transport.assert_request(
+            "eth_getTransactionReceipt",
+            &[r#""0x0000000000000000000000000000000000000000000000000000000000000001""#.into()],
+        );
+        transport.assert_request(
+            "eth_getTransactionCount",
+            &[
+                r#""0x0000000000000000000000000000000000000123""#.into(),
+                r#""latest""#.into(),
+            ],
+        );
+        transport.assert_request(
+            "eth_getTransactionByHash",
+            &[r#""0x0000000000000000000000000000000000000000000000000000000000000001""#.into()],
+        );
+    }
+
+    #[test]
+    fn replaced_and_dropped() {
+        let mut transport = TestTransport::default();
+        let eth = Eth::new(transport.clone());
+
+        let mut tracker = tracker();
+        tracker.add_replacement(H256::from_low_u64_be(2));
+        transport.add_response(Value::Null);
+        transport.add_response(receipt(H256::from_low_u64_be(2), 11, H256::from_low_u64_be(0xb)));
+        assert_eq!(
+            block_on(tracker.poll(&eth)),
+            Ok(TxStatus::Replaced(H256::from_low_u64_be(2)))
+        );
+
+        let mut tracker = self::tracker();
+        transport.add_response(Value::Null);
+        transport.add_response(Value::String("0x6".into()));
+        transport.add_response(Value::Null);
+        assert_eq!(block_on(tracker.poll(&eth)), Ok(TxStatus::Dropped));
+
+        // unknown to the node, but only just submitted
+        transport.add_response(Value::Null);
+        transport.add_response(Value::String("0x5".into()));
+        transport.add_response(Value::Null);
+        transport.add_response(Value::String("0xc".into()));
+        assert_eq!(block_on(tracker.poll(&eth)), Ok(TxStatus::Pending));
+
+        transport.add_response(Value::Null);
+        transport.add_response(Value::String("0x5".into()));
+        transport.add_response(Value::Null);
+        transport.add_response(Value::String("0xd".into()));
+        assert_eq!(block_on(tracker.poll(&eth)), Ok(TxStatus::Dropped));
+    }
+
+    #[test]
+    fn mined_while_nonce_checked() {
+        let mut transport = TestTransport::default();
+        let eth = Eth::new(transport.clone());
+        let mut tracker = tracker();
+
+        transport.add_response(Value::Null);
+        transport.add_response(Value::String("0x6".into()));
+        transport.add_response(receipt(H256::from_low_u64_be(1), 11, H256::from_low_u64_be(0xb)));
+        transport.add_response(Value::String("0xb".into()));
+        assert_eq!(block_on(tracker.poll(&eth)), Ok(TxStatus::Mined(1)));
+
+        let receipt_request = [r#""0x0000000000000000000000000000000000000000000000000000000000000001""#.into()];
+        transport.assert_request("eth_getTransactionReceipt", &receipt_request);
+        transport.assert_request(
+            "eth_getTransactionCount",
+            &[
+                r#""0x0000000000000000000000000000000000000123""#.into(),
+                r#""latest""#.into(),
+            ],
+        );
+        transport.assert_request("eth_getTransactionReceipt", &receipt_request);
+        transport.assert_request("eth_blockNumber", &[]);
+        transport.assert_no_more_requests();
+    }
+
+    #[test]
+    fn reorged() {
+        let mut transport = TestTransport::default();
+        let eth = Eth::new(transport.clone());
+        let mut tracker = tracker();
+
+        transport.add_response(receipt(H256::from_low_u64_be(1), 11, H256::from_low_u64_be(0xb)));
+        transport.add_response(Value::String("0xb".into()));
+        assert_eq!(block_on(tracker.poll(&eth)), Ok(TxStatus::Mined(1)));
+
+        transport.add_response(receipt(H256::from_low_u64_be(1), 12, H256::from_low_u64_be(0xc)));
+        assert_eq!(block_on(tracker.poll(&eth)), Ok(TxStatus::Reorged));
+
+        transport.add_response(Value::Null);
+        assert_eq!(block_on(tracker.poll(&eth)), Ok(TxStatus::Reorged));
+        assert_eq!(tracker.mined_block, None);
+    }
+
+    #[cfg(feature = "signing")]
+    #[test]
+    fn tracks_fees_of_signed_transactions() {
+        use crate::{keys::secret::SecretKey, signing::Key, types::TransactionParameters};
+        use hex_literal::hex;
+
+        let key = SecretKey::from_slice(&hex!(
+            "4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318"
+        ))
+        .unwrap();
+        let params = TransactionParameters {
+            nonce: Some(5.into()),
+            to: Some(Address::from_low_u64_be(0x456)),
+            gas: Some(21_000.into()),
+            gas_price: Some(10.into()),
+            chain_id: Some(1),
+            ..Default::default()
+        };
+        let signed = params.clone().sign(&key).unwrap();
+        let tracker = TxTracker::from_signed(&signed, 10).unwrap();
+        assert_eq!(tracker.hash, signed.transaction_hash);
+        assert_eq!(tracker.sender, key.address());
+        assert_eq!(tracker.nonce, 5.into());
+        assert_eq!(tracker.gas_price, Some(10.into()));
+        assert_eq!(tracker.max_fee_per_gas, None);
+
+        let mut tracker: TxTracker = serde_json::from_value(serde_json::to_value(&tracker).unwrap()).unwrap();
+        assert_eq!(tracker.gas_price, Some(10.into()));
+        let replacement = TransactionParameters {
+            transaction_type: Some(2.into()),
+            max_fee_per_gas: Some(20.into()),
+            max_priority_fee_per_gas: Some(2.into()),
+            ..params.clone()
+        }
+        .sign(&key)
+        .unwrap();
+        tracker.add_signed_replacement(&replacement).unwrap();
+        assert_eq!(tracker.latest_hash(), replacement.transaction_hash);
+        assert_eq!(tracker.gas_price, None);
+        assert_eq!(tracker.max_fee_per_gas, Some(20.into()));
+        assert_eq!(tracker.max_priority_fee_per_gas, Some(2.into()));
+
+        let other_nonce = TransactionParameters {
+            nonce: Some(6.into()),
+            ..params
+        }
+        .sign(&key)
+        .unwrap();
+        assert_eq!(
+            tracker.add_signed_replacement(&other_nonce),
+            Err(error::Error::InvalidParameter("replacement"))
+        );
+        assert_eq!(tracker.replacements, vec![replacement.transaction_hash]);
+    }
+}
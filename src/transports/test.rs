@@ -1,5 +1,5 @@
 //! Test Transport
-use crate::{error, helpers::json_rpc, rpc, Transport};
+use crate::{error, helpers::json_rpc, rpc, types::H256, Transport};
 use core::future::Ready;
 use std::{cell::RefCell, collections::VecDeque, rc::Rc};
 
@@ -8,7 +8,7 @@ use std::{cell::RefCell, collections::VecDeque, rc::Rc};
 pub struct TestTransport {
     asserted: usize,
     requests: Rc<RefCell<Vec<(String, String)>>>,
-    responses: Rc<RefCell<VecDeque<rpc::Value>>>,
+    responses: Rc<RefCell<VecDeque<Result<rpc::Value, rpc::Value>>>>,
 }
 
 impl Transport for TestTransport {
@@ -18,7 +18,16 @@ impl Transport for TestTransport {
         let request = json_rpc::encode_request(method, params);
         self.requests.borrow_mut().push((method.into(), request));
         let response = self.responses.borrow_mut().pop_front().unwrap();
-        let returning = format!(r#"{{ "id": 0, "jsonrpc": "2.0", "result": {} }}"#, serde_json::to_string(&response).unwrap());
+        let returning = match response {
+            Ok(result) => format!(
+                r#"{{ "id": 0, "jsonrpc": "2.0", "result": {} }}"#,
+                serde_json::to_string(&result).unwrap()
+            ),
+            Err(error) => format!(
+                r#"{{ "id": 0, "jsonrpc": "2.0", "error": {} }}"#,
+                serde_json::to_string(&error).unwrap()
+            ),
+        };
         core::future::ready(Ok(returning.as_bytes().to_vec()))
     }
 }
@@ -26,12 +35,24 @@ impl Transport for TestTransport {
 impl TestTransport {
     /// Set response
     pub fn set_response(&mut self, value: rpc::Value) {
-        *self.responses.borrow_mut() = vec![value].into();
+        *self.responses.borrow_mut() = vec![Ok(value)].into();
     }
 
     /// Add response
     pub fn add_response(&mut self, value: rpc::Value) {
-        self.responses.borrow_mut().push_back(value);
+        self.responses.borrow_mut().push_back(Ok(value));
+    }
+
+    /// Add an rpc error response
+    pub fn add_error(&mut self, code: i64, message: &str) {
+        let error = serde_json::json!({ "code": code, "message": message });
+        self.responses.borrow_mut().push_back(Err(error));
+    }
+
+    /// Add an rpc error response carrying `data`
+    pub fn add_error_with_data(&mut self, code: i64, message: &str, data: rpc::Value) {
+        let error = serde_json::json!({ "code": code, "message": message, "data": data });
+        self.responses.borrow_mut().push_back(Err(error));
     }
 
     /// Assert request
@@ -59,3 +80,25 @@ impl TestTransport {
         );
     }
 }
+
+/// Minimal `eth_getBlockByNumber` response, without transactions.
+pub fn block_response(number: u64, hash: H256, parent_hash: H256) -> rpc::Value {
+    serde_json::json!({
+        "number": format!("{:#x}", number),
+        "hash": hash,
+        "parentHash": parent_hash,
+        "sha3Uncles": H256::zero(),
+        "miner": null,
+        "stateRoot": H256::zero(),
+        "transactionsRoot": H256::zero(),
+        "receiptsRoot": H256::zero(),
+        "gasUsed": "0x0",
+        "gasLimit": "0x0",
+        "extraData": "0x",
+        "logsBloom": format!("0x{}", "0".repeat(512)),
+        "timestamp": "0x0",
+        "difficulty": "0x0",
+        "uncles": [],
+        "transactions": [],
+    })
+}
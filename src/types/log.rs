@@ -97,6 +97,26 @@ pub struct Filter {
     limit: Option<usize>,
 }
 
+impl Filter {
+    /// Returns the numeric block range of the filter, if both ends are block numbers.
+    pub fn block_range(&self) -> Option<(u64, u64)> {
+        match (self.from_block, self.to_block) {
+            (Some(BlockNumber::Number(from)), Some(BlockNumber::Number(to))) => Some((from.as_u64(), to.as_u64())),
+            _ => None,
+        }
+    }
+
+    /// Returns a copy of the filter restricted to the given block range.
+    pub fn with_block_range(&self, from: u64, to: u64) -> Filter {
+        Filter {
+            from_block: Some(BlockNumber::Number(from.into())),
+            to_block: Some(BlockNumber::Number(to.into())),
+            block_hash: None,
+            ..self.clone()
+        }
+    }
+}
+
 /// Filter Builder
 #[derive(Default, Clone)]
 pub struct FilterBuilder {
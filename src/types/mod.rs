@@ -5,11 +5,14 @@ mod bytes;
 mod bytes_array;
 mod fee_history;
 mod log;
+mod otterscan;
 mod parity_peers;
 mod parity_pending_transaction;
 mod proof;
 mod recovery;
 mod signed;
+mod simulate;
+mod state_override;
 mod sync_state;
 mod trace_filtering;
 mod traces;
@@ -26,6 +29,10 @@ pub use self::{
     bytes_array::BytesArray,
     fee_history::FeeHistory,
     log::{Filter, FilterBuilder, Log},
+    otterscan::{
+        ContractCreator, InternalOperation, InternalOperationType, OtsReceipt, OtsTrace, OtsTraceType,
+        OtsTransactionsPage,
+    },
     parity_peers::{
         EthProtocolInfo, ParityPeerInfo, ParityPeerType, PeerNetworkInfo, PeerProtocolsInfo, PipProtocolInfo,
     },
@@ -34,7 +41,12 @@ pub use self::{
     },
     proof::Proof,
     recovery::{ParseSignatureError, Recovery, RecoveryMessage},
-    signed::{SignedData, SignedTransaction, TransactionParameters},
+    signed::{
+        BlobSidecar, ReplacedTransaction, Replacement, SignedData, SignedTransaction, TransactionParameters,
+        UnsignedTransaction, DEFAULT_GAS_MULTIPLIER_PERCENT,
+    },
+    simulate::{SimulateBlock, SimulateError, SimulatePayload, SimulatedBlock, SimulatedCall},
+    state_override::{AccountOverride, BlockOverrides, StateOverride},
     sync_state::{SyncInfo, SyncState},
     trace_filtering::{
         Action, ActionType, Call, CallResult, CallType, Create, CreateResult, Res, Reward, RewardType, Suicide, Trace,
@@ -44,7 +56,10 @@ pub use self::{
         AccountDiff, BlockTrace, ChangedType, Diff, MemoryDiff, StateDiff, StorageDiff, TraceType, TransactionTrace,
         VMExecutedOperation, VMOperation, VMTrace,
     },
-    transaction::{AccessList, AccessListItem, RawTransaction, Receipt as TransactionReceipt, Transaction},
+    transaction::{
+        AccessList, AccessListItem, Authorization, AuthorizationList, DecodedTransaction, RawTransaction,
+        Receipt as TransactionReceipt, SignedAuthorization, Transaction,
+    },
     transaction_id::TransactionId,
     transaction_request::{CallRequest, TransactionCondition, TransactionRequest},
     txpool::{TxpoolContentInfo, TxpoolInspectInfo, TxpoolStatus},
@@ -0,0 +1,206 @@
+//! Types for the Otterscan (`ots_*`) API
+use crate::prelude::*;
+use crate::types::{Address, Bytes, Transaction, H256, U256, U64};
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+/// Creator of a contract, returned by `ots_getContractCreator`.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ContractCreator {
+    /// Hash of the transaction that created the contract.
+    pub hash: H256,
+    /// Address that deployed the contract (an EOA or a factory contract).
+    pub creator: Address,
+}
+
+/// A page of transactions returned by `ots_searchTransactionsBefore` and `ots_searchTransactionsAfter`.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OtsTransactionsPage {
+    /// Transactions touching the searched address.
+    pub txs: Vec<Transaction>,
+    /// Receipts of the transactions, in the same order.
+    pub receipts: Vec<OtsReceipt>,
+    /// Whether this is the page with the most recent transactions.
+    pub first_page: bool,
+    /// Whether this is the page with the oldest transactions.
+    pub last_page: bool,
+}
+
+/// Receipt returned as part of an [`OtsTransactionsPage`].
+///
+/// Otterscan strips logs and bloom from these receipts and adds the block timestamp instead.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OtsReceipt {
+    /// Transaction hash.
+    pub transaction_hash: H256,
+    /// Hash of the block this transaction was included within.
+    pub block_hash: Option<H256>,
+    /// Number of the block this transaction was included within.
+    pub block_number: Option<U64>,
+    /// Sender
+    #[serde(default)]
+    pub from: Address,
+    /// Recipient (None when contract creation)
+    #[serde(default)]
+    pub to: Option<Address>,
+    /// Gas used by this transaction alone.
+    pub gas_used: Option<U256>,
+    /// Contract address created, or `None` if not a deployment.
+    #[serde(default)]
+    pub contract_address: Option<Address>,
+    /// Status: either 1 (success) or 0 (failure).
+    pub status: Option<U64>,
+    /// Effective gas price
+    #[serde(default)]
+    pub effective_gas_price: Option<U256>,
+    /// Timestamp of the block this transaction was included within.
+    #[serde(default)]
+    pub timestamp: Option<U64>,
+}
+
+/// Kind of a call frame returned by `ots_traceTransaction`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum OtsTraceType {
+    /// Regular call
+    Call,
+    /// Delegate call
+    DelegateCall,
+    /// Static call
+    StaticCall,
+    /// Call code
+    CallCode,
+    /// Contract creation
+    Create,
+    /// Contract creation with salt
+    Create2,
+    /// Self destruct
+    SelfDestruct,
+}
+
+/// A call frame returned by `ots_traceTransaction`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct OtsTrace {
+    /// Kind of the call
+    #[serde(rename = "type")]
+    pub trace_type: OtsTraceType,
+    /// Call depth, starting at 0 for the top level call.
+    pub depth: u32,
+    /// Caller
+    pub from: Address,
+    /// Callee
+    pub to: Address,
+    /// Transferred value (None for calls that can't carry value)
+    pub value: Option<U256>,
+    /// Call input
+    #[serde(default)]
+    pub input: Bytes,
+}
+
+/// Kind of an internal operation returned by `ots_getInternalOperations`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InternalOperationType {
+    /// ETH transfer from a contract
+    Transfer,
+    /// Self destruct sending the remaining balance
+    SelfDestruct,
+    /// Contract creation
+    Create,
+    /// Contract creation with salt
+    Create2,
+}
+
+impl Serialize for InternalOperationType {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let value = match *self {
+            InternalOperationType::Transfer => 0,
+            InternalOperationType::SelfDestruct => 1,
+            InternalOperationType::Create => 2,
+            InternalOperationType::Create2 => 3,
+        };
+        serializer.serialize_u8(value)
+    }
+}
+
+impl<'a> Deserialize<'a> for InternalOperationType {
+    fn deserialize<D>(deserializer: D) -> Result<InternalOperationType, D::Error>
+    where
+        D: Deserializer<'a>,
+    {
+        match u8::deserialize(deserializer)? {
+            0 => Ok(InternalOperationType::Transfer),
+            1 => Ok(InternalOperationType::SelfDestruct),
+            2 => Ok(InternalOperationType::Create),
+            3 => Ok(InternalOperationType::Create2),
+            other => Err(D::Error::custom(format!("invalid internal operation type: {}", other))),
+        }
+    }
+}
+
+/// A value transfer that doesn't show up as a regular transaction, returned by `ots_getInternalOperations`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct InternalOperation {
+    /// Kind of the operation
+    #[serde(rename = "type")]
+    pub operation_type: InternalOperationType,
+    /// Source
+    pub from: Address,
+    /// Destination
+    pub to: Address,
+    /// Transferred value
+    pub value: U256,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserialize_trace() {
+        let traces: Vec<OtsTrace> = serde_json::from_str(
+            r#"[{
+                "type": "CALL",
+                "depth": 0,
+                "from": "0x0000000000000000000000000000000000000001",
+                "to": "0x0000000000000000000000000000000000000002",
+                "value": "0x10",
+                "input": "0x"
+            }, {
+                "type": "DELEGATECALL",
+                "depth": 1,
+                "from": "0x0000000000000000000000000000000000000002",
+                "to": "0x0000000000000000000000000000000000000003",
+                "value": null,
+                "input": "0x12345678"
+            }]"#,
+        )
+        .unwrap();
+
+        assert_eq!(traces[0].trace_type, OtsTraceType::Call);
+        assert_eq!(traces[0].value, Some(0x10.into()));
+        assert_eq!(traces[1].trace_type, OtsTraceType::DelegateCall);
+        assert_eq!(traces[1].depth, 1);
+        assert_eq!(traces[1].value, None);
+    }
+
+    #[test]
+    fn deserialize_internal_operations() {
+        let operations: Vec<InternalOperation> = serde_json::from_str(
+            r#"[{
+                "type": 2,
+                "from": "0x0000000000000000000000000000000000000001",
+                "to": "0x0000000000000000000000000000000000000002",
+                "value": "0x0"
+            }]"#,
+        )
+        .unwrap();
+
+        assert_eq!(operations[0].operation_type, InternalOperationType::Create);
+        assert_eq!(serde_json::to_value(&operations[0]).unwrap()["type"], 2);
+        assert!(serde_json::from_str::<InternalOperationType>("4").is_err());
+    }
+}
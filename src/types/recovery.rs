@@ -1,12 +1,13 @@
 use crate::prelude::*;
-use crate::types::{SignedData, SignedTransaction, H256};
+use crate::types::{SignedAuthorization, SignedData, SignedTransaction, H256};
 use core::fmt::{Display, Formatter, Result as FmtResult};
 
 /// Data for recovering the public address of signed data.
 ///
 /// Note that the signature data is in 'Electrum' notation and may have chain
 /// replay protection applied. That means that `v` is expected to be `27`, `28`,
-/// or `35 + chain_id * 2` or `36 + chain_id * 2`.
+/// or `35 + chain_id * 2` or `36 + chain_id * 2`. The y-parity of typed
+/// transactions, `0` or `1`, is accepted as well.
 #[derive(Clone, Debug, PartialEq)]
 pub struct Recovery {
     /// The message to recover
@@ -62,6 +63,7 @@ impl Recovery {
     /// (equivalent of returning `4` in some implementaions).
     pub fn recovery_id(&self) -> Option<i32> {
         match self.v {
+            0 | 1 => Some(self.v as _),
             27 => Some(0),
             28 => Some(1),
             v if v >= 35 => Some(((v - 1) % 2) as _),
@@ -95,6 +97,15 @@ impl<'a> From<&'a SignedTransaction> for Recovery {
     }
 }
 
+impl<'a> From<&'a SignedAuthorization> for Recovery {
+    fn from(auth: &'a SignedAuthorization) -> Self {
+        let (mut r, mut s) = (H256::zero(), H256::zero());
+        auth.r.to_big_endian(r.as_bytes_mut());
+        auth.s.to_big_endian(s.as_bytes_mut());
+        Recovery::new(auth.authorization().signature_hash(), auth.y_parity.as_u64() + 27, r, s)
+    }
+}
+
 /// Recovery message data.
 ///
 /// The message data can either be a binary message that is first hashed
@@ -1,5 +1,5 @@
 use crate::prelude::*;
-use crate::types::{AccessList, Address, Bytes, CallRequest, H256, U256, U64};
+use crate::types::{AccessList, Address, AuthorizationList, Bytes, CallRequest, H256, U256, U64};
 use serde::{Deserialize, Serialize};
 
 /// Struct representing signed data returned from `Accounts::sign` method.
@@ -32,20 +32,19 @@ pub struct SignedData {
 /// They happen to be the same much of the time but it is recommended to set
 /// this for signing transactions.
 ///
-/// `TransactionParameters` implements `Default` and uses `100_000` as the
-/// default `gas` to use for the transaction. This is more than enough for
-/// simple transactions sending ETH between accounts but may not be enough when
-/// interacting with complex contracts. It is recommended when interacting
-/// with contracts to use `Eth::estimate_gas` to estimate the required gas for
-/// the transaction.
+/// When `gas` is omitted, it is estimated with `Eth::estimate_gas` and
+/// multiplied by `gas_multiplier_percent` to leave some headroom. A reverting
+/// estimation fails the signing with `Error::Revert`.
 #[derive(Clone, Debug, PartialEq)]
 pub struct TransactionParameters {
     /// Transaction nonce (None for account transaction count)
     pub nonce: Option<U256>,
     /// To address
     pub to: Option<Address>,
-    /// Supplied gas
-    pub gas: U256,
+    /// Supplied gas (None for estimated gas)
+    pub gas: Option<U256>,
+    /// Percentage of the estimated gas supplied when `gas` is None
+    pub gas_multiplier_percent: u64,
     /// Gas price (None for estimated gas price)
     pub gas_price: Option<U256>,
     /// Transferred value
@@ -62,22 +61,26 @@ pub struct TransactionParameters {
     pub max_fee_per_gas: Option<U256>,
     /// miner bribe
     pub max_priority_fee_per_gas: Option<U256>,
+    /// Max fee per blob gas of blob transactions (None for twice the blob base fee)
+    pub max_fee_per_blob_gas: Option<U256>,
+    /// Versioned hashes of the blobs (None for the hashes of the sidecar's commitments)
+    pub blob_versioned_hashes: Option<Vec<H256>>,
+    /// Blobs sent along a blob transaction, making it the network form (None for the bare form)
+    pub blob_sidecar: Option<BlobSidecar>,
+    /// Authorization list of set-code transactions
+    pub authorization_list: Option<AuthorizationList>,
 }
 
-/// The default fas for transactions.
-///
-/// Unfortunately there is no way to construct `U256`s with const functions for
-/// constants so we just build it from it's `u64` words. Note that there is a
-/// unit test to verify that it is constructed correctly and holds the expected
-/// value of 100_000.
-const TRANSACTION_DEFAULT_GAS: U256 = U256([100_000, 0, 0, 0]);
+/// The default percentage of the estimated gas supplied to transactions.
+pub const DEFAULT_GAS_MULTIPLIER_PERCENT: u64 = 120;
 
 impl Default for TransactionParameters {
     fn default() -> Self {
         TransactionParameters {
             nonce: None,
             to: None,
-            gas: TRANSACTION_DEFAULT_GAS,
+            gas: None,
+            gas_multiplier_percent: DEFAULT_GAS_MULTIPLIER_PERCENT,
             gas_price: None,
             value: U256::zero(),
             data: Bytes::default(),
@@ -86,6 +89,10 @@ impl Default for TransactionParameters {
             access_list: None,
             max_fee_per_gas: None,
             max_priority_fee_per_gas: None,
+            max_fee_per_blob_gas: None,
+            blob_versioned_hashes: None,
+            blob_sidecar: None,
+            authorization_list: None,
         }
     }
 }
@@ -95,7 +102,8 @@ impl From<CallRequest> for TransactionParameters {
         TransactionParameters {
             nonce: None,
             to: call.to,
-            gas: call.gas.unwrap_or(TRANSACTION_DEFAULT_GAS),
+            gas: call.gas,
+            gas_multiplier_percent: DEFAULT_GAS_MULTIPLIER_PERCENT,
             gas_price: call.gas_price,
             value: call.value.unwrap_or_default(),
             data: call.data.unwrap_or_default(),
@@ -104,6 +112,10 @@ impl From<CallRequest> for TransactionParameters {
             access_list: call.access_list,
             max_fee_per_gas: call.max_fee_per_gas,
             max_priority_fee_per_gas: call.max_priority_fee_per_gas,
+            max_fee_per_blob_gas: None,
+            blob_versioned_hashes: None,
+            blob_sidecar: None,
+            authorization_list: None,
         }
     }
 }
@@ -113,7 +125,7 @@ impl From<TransactionParameters> for CallRequest {
         CallRequest {
             from: None,
             to: val.to,
-            gas: Some(val.gas),
+            gas: val.gas,
             gas_price: val.gas_price,
             value: Some(val.value),
             data: Some(val.data),
@@ -125,6 +137,31 @@ impl From<TransactionParameters> for CallRequest {
     }
 }
 
+/// Blobs of an EIP-4844 transaction with their KZG commitments and proofs.
+///
+/// The commitments and proofs are computed by the caller; the sidecar is only RLP-encoded
+/// along the signed transaction, as expected by `eth_sendRawTransaction`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BlobSidecar {
+    /// Blobs, 128 KiB each
+    pub blobs: Vec<Bytes>,
+    /// KZG commitments of the blobs, 48 bytes each
+    pub commitments: Vec<Bytes>,
+    /// KZG proofs of the blobs, 48 bytes each
+    pub proofs: Vec<Bytes>,
+}
+
+#[cfg(feature = "signing")]
+impl BlobSidecar {
+    /// Versioned hashes of the commitments, as included in the transaction.
+    pub fn versioned_hashes(&self) -> Vec<H256> {
+        self.commitments
+            .iter()
+            .map(|commitment| crate::signing::blob_versioned_hash(&commitment.0))
+            .collect()
+    }
+}
+
 /// Data for offline signed transaction
 #[derive(Clone, Debug, PartialEq)]
 pub struct SignedTransaction {
@@ -142,12 +179,94 @@ pub struct SignedTransaction {
     pub transaction_hash: H256,
 }
 
+/// Transaction waiting for a signature produced outside of the crate, e.g. by an MPC service or
+/// a hardware-backed signer.
+///
+/// Created with `TransactionParameters::unsigned` or `Accounts::prepare_transaction`, and
+/// turned into a `SignedTransaction` with `attach_signature` once the signing hash is signed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct UnsignedTransaction {
+    pub(crate) parameters: TransactionParameters,
+    pub(crate) from: Address,
+    pub(crate) payload: Bytes,
+    pub(crate) signing_hash: H256,
+}
+
+impl UnsignedTransaction {
+    /// Fully specified parameters of the transaction.
+    pub fn parameters(&self) -> &TransactionParameters {
+        &self.parameters
+    }
+
+    /// Address expected to sign the transaction.
+    pub fn from(&self) -> Address {
+        self.from
+    }
+
+    /// Unsigned envelope, for signers hashing the payload themselves.
+    pub fn payload(&self) -> &Bytes {
+        &self.payload
+    }
+
+    /// Keccak-256 hash of the payload, to be signed without any prefix.
+    pub fn signing_hash(&self) -> H256 {
+        self.signing_hash
+    }
+}
+
+/// How `Accounts::sign_replacement` replaces a pending transaction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Replacement {
+    /// Re-sign the same transaction with higher fees.
+    SpeedUp,
+    /// Re-sign a zero-value transfer to the sender itself, so the original can't be mined.
+    Cancel,
+}
+
+/// Pending transaction replaced by `Accounts::sign_replacement`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ReplacedTransaction {
+    /// Transaction looked up by hash, which must still be known to the node.
+    Hash(H256),
+    /// Blob transaction looked up by hash, with the sidecar it was sent with. Nodes don't return
+    /// sidecars, which a sped-up blob transaction must carry again.
+    Blob(H256, BlobSidecar),
+    /// Transaction decoded from its raw bytes, with its sidecar if in network form.
+    Signed(SignedTransaction),
+}
+
+impl From<H256> for ReplacedTransaction {
+    fn from(hash: H256) -> Self {
+        ReplacedTransaction::Hash(hash)
+    }
+}
+
+impl From<(H256, BlobSidecar)> for ReplacedTransaction {
+    fn from((hash, sidecar): (H256, BlobSidecar)) -> Self {
+        ReplacedTransaction::Blob(hash, sidecar)
+    }
+}
+
+impl From<SignedTransaction> for ReplacedTransaction {
+    fn from(tx: SignedTransaction) -> Self {
+        ReplacedTransaction::Signed(tx)
+    }
+}
+
+impl From<&SignedTransaction> for ReplacedTransaction {
+    fn from(tx: &SignedTransaction) -> Self {
+        ReplacedTransaction::Signed(tx.clone())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
-    fn verify_transaction_default_gas() {
-        assert_eq!(TRANSACTION_DEFAULT_GAS, U256::from(100_000));
+    fn transaction_default_gas_is_estimated() {
+        let tx = TransactionParameters::default();
+        assert_eq!(tx.gas, None);
+        assert_eq!(tx.gas_multiplier_percent, 120);
     }
 }
@@ -0,0 +1,261 @@
+use crate::prelude::*;
+use crate::{
+    contract::{self, revert::RevertReason, tokens::Detokenize},
+    types::{BlockOverrides, Bytes, CallRequest, Log, StateOverride, H256, U256, U64},
+};
+use serde::{Deserialize, Serialize};
+
+/// Payload of an `eth_simulateV1` request.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatePayload {
+    /// Blocks to simulate, executed in order on top of each other.
+    pub block_state_calls: Vec<SimulateBlock>,
+    /// Report ETH transfers as synthetic logs.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub trace_transfers: bool,
+    /// Run the same checks as a real transaction (nonce, balance, base fee).
+    ///
+    /// When disabled (the default) calls behave like `eth_call`.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub validation: bool,
+    /// Return full transaction objects instead of hashes in the simulated blocks.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub return_full_transactions: bool,
+}
+
+impl SimulatePayload {
+    /// Appends a simulated block.
+    pub fn block(mut self, block: SimulateBlock) -> Self {
+        self.block_state_calls.push(block);
+        self
+    }
+
+    /// Enables transfer tracing.
+    pub fn trace_transfers(mut self, enabled: bool) -> Self {
+        self.trace_transfers = enabled;
+        self
+    }
+
+    /// Enables transaction validation.
+    pub fn validation(mut self, enabled: bool) -> Self {
+        self.validation = enabled;
+        self
+    }
+}
+
+/// A single block of an `eth_simulateV1` request: overrides followed by a sequence of calls.
+///
+/// Calls within a block see the state changes made by the previous ones, which allows simulating
+/// dependent transactions such as an approval followed by a swap.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulateBlock {
+    /// Block header fields to override.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_overrides: Option<BlockOverrides>,
+    /// Account state to override before the first call.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state_overrides: Option<StateOverride>,
+    /// Calls to execute, in order.
+    pub calls: Vec<CallRequest>,
+}
+
+impl SimulateBlock {
+    /// Appends a call to the block.
+    pub fn call(mut self, call: CallRequest) -> Self {
+        self.calls.push(call);
+        self
+    }
+
+    /// Sets the block overrides.
+    pub fn block_overrides(mut self, overrides: BlockOverrides) -> Self {
+        self.block_overrides = Some(overrides);
+        self
+    }
+
+    /// Sets the state overrides.
+    pub fn state_overrides(mut self, overrides: StateOverride) -> Self {
+        self.state_overrides = Some(overrides);
+        self
+    }
+}
+
+/// A block returned by `eth_simulateV1`.
+///
+/// Only the header fields relevant for simulations are decoded.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedBlock {
+    /// Block number
+    pub number: U64,
+    /// Block hash
+    pub hash: H256,
+    /// Parent block hash
+    pub parent_hash: H256,
+    /// Block timestamp
+    pub timestamp: U64,
+    /// Gas limit
+    pub gas_limit: U256,
+    /// Gas used by all the calls
+    pub gas_used: U256,
+    /// Base fee per gas
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_fee_per_gas: Option<U256>,
+    /// Results of the simulated calls, in request order.
+    pub calls: Vec<SimulatedCall>,
+}
+
+/// Result of a single call executed by `eth_simulateV1`.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedCall {
+    /// Returned data, or the revert data if the call failed.
+    pub return_data: Bytes,
+    /// Logs emitted by the call.
+    #[serde(default)]
+    pub logs: Vec<Log>,
+    /// Gas used by the call.
+    pub gas_used: U64,
+    /// Status: either 1 (success) or 0 (failure).
+    pub status: U64,
+    /// Error details when the call failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<SimulateError>,
+}
+
+impl SimulatedCall {
+    /// Returns true if the call succeeded.
+    pub fn is_success(&self) -> bool {
+        self.status == U64::one()
+    }
+
+    /// Returns the revert data of a failed call.
+    pub fn revert_data(&self) -> Option<&Bytes> {
+        if self.is_success() {
+            None
+        } else {
+            Some(&self.return_data)
+        }
+    }
+
+    /// Decodes why the call reverted, looking up custom errors in `abi` if given.
+    ///
+    /// Returns `None` if the call succeeded or failed without reverting, e.g. by running out of gas.
+    pub fn revert_reason(&self, abi: Option<&ethabi::Contract>) -> Option<RevertReason> {
+        let data = self.revert_data()?;
+        let reverted = !data.0.is_empty() || self.error.as_ref().is_some_and(|error| error.code == 3);
+        reverted.then(|| RevertReason::decode(&data.0, abi))
+    }
+
+    /// Decodes the returned data as the output of the given function.
+    ///
+    /// A revert is returned as `contract::Error::Revert`.
+    pub fn decode<R: Detokenize>(&self, function: &ethabi::Function) -> contract::Result<R> {
+        if let Some(reason) = self.revert_reason(None) {
+            return Err(contract::Error::Revert(reason));
+        }
+        if !self.is_success() {
+            let message = match self.error {
+                Some(ref error) => error.message.clone(),
+                None => "simulated call failed".into(),
+            };
+            return Err(crate::Error::Rpc(message).into());
+        }
+        let output = function.decode_output(&self.return_data.0)?;
+        R::from_tokens(output)
+    }
+}
+
+/// Error of a failed simulated call.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct SimulateError {
+    /// Error code
+    pub code: i64,
+    /// Human readable message
+    pub message: String,
+    /// Revert data, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Bytes>,
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Address;
+
+    #[test]
+    fn serialize_simulate_payload() {
+        let payload = SimulatePayload::default()
+            .block(
+                SimulateBlock::default()
+                    .call(CallRequest {
+                        to: Some(Address::from_low_u64_be(0x123)),
+                        ..Default::default()
+                    })
+                    .block_overrides(BlockOverrides {
+                        number: Some(0x10.into()),
+                        ..Default::default()
+                    }),
+            )
+            .validation(true);
+
+        let serialized = serde_json::to_value(&payload).unwrap();
+        assert_eq!(
+            serialized,
+            serde_json::json!({
+                "blockStateCalls": [{
+                    "blockOverrides": {"number": "0x10"},
+                    "calls": [{"to": "0x0000000000000000000000000000000000000123"}]
+                }],
+                "validation": true
+            })
+        );
+    }
+
+    #[test]
+    fn decode_simulated_call() {
+        let call: SimulatedCall = serde_json::from_str(
+            r#"{
+            "returnData": "0x0000000000000000000000000000000000000000000000000000000000000020",
+            "logs": [],
+            "gasUsed": "0x5208",
+            "status": "0x1"
+        }"#,
+        )
+        .unwrap();
+        let abi: ethabi::Contract = serde_json::from_slice(include_bytes!("../contract/res/token.json")).unwrap();
+        let function = abi.function("balanceOf").unwrap();
+
+        assert!(call.is_success());
+        assert_eq!(call.revert_data(), None);
+        let value: U256 = call.decode(function).unwrap();
+        assert_eq!(value, 0x20.into());
+    }
+
+    #[test]
+    fn failed_simulated_call() {
+        let call: SimulatedCall = serde_json::from_str(
+            r#"{
+            "returnData": "0x08c379a0",
+            "logs": [],
+            "gasUsed": "0x5208",
+            "status": "0x0",
+            "error": {"code": 3, "message": "execution reverted", "data": "0x08c379a0"}
+        }"#,
+        )
+        .unwrap();
+
+        assert!(!call.is_success());
+        assert_eq!(call.revert_data(), Some(&Bytes(vec![0x08, 0xc3, 0x79, 0xa0])));
+        assert_eq!(
+            call.revert_reason(None),
+            Some(RevertReason::Raw(vec![0x08, 0xc3, 0x79, 0xa0].into()))
+        );
+        assert_eq!(call.error.unwrap().message, "execution reverted");
+    }
+}
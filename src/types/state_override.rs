@@ -0,0 +1,158 @@
+use crate::types::{Address, Bytes, H256, U256, U64};
+use alloc::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+
+/// State overrides passed as the third parameter of `eth_call`.
+///
+/// Maps an account address to the fields that should be replaced before the call is executed.
+pub type StateOverride = BTreeMap<Address, AccountOverride>;
+
+/// Overrides applied to a single account during `eth_call`.
+///
+/// `state` and `state_diff` are mutually exclusive: `state` replaces the whole storage of the
+/// account while `state_diff` only patches the given slots.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountOverride {
+    /// Fake balance to set for the account.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub balance: Option<U256>,
+    /// Fake nonce to set for the account.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<U64>,
+    /// Fake EVM bytecode to inject into the account.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<Bytes>,
+    /// Fake key-value mapping to override all slots in the account storage.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<BTreeMap<H256, H256>>,
+    /// Fake key-value mapping to override individual slots in the account storage.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state_diff: Option<BTreeMap<H256, H256>>,
+}
+
+impl AccountOverride {
+    /// Sets the balance override.
+    pub fn balance(mut self, balance: U256) -> Self {
+        self.balance = Some(balance);
+        self
+    }
+
+    /// Sets the nonce override.
+    pub fn nonce(mut self, nonce: U64) -> Self {
+        self.nonce = Some(nonce);
+        self
+    }
+
+    /// Sets the code override.
+    pub fn code(mut self, code: Bytes) -> Self {
+        self.code = Some(code);
+        self
+    }
+
+    /// Replaces the whole account storage. Clears a previously set `state_diff`.
+    pub fn state(mut self, state: BTreeMap<H256, H256>) -> Self {
+        self.state_diff = None;
+        self.state = Some(state);
+        self
+    }
+
+    /// Patches individual storage slots. Clears a previously set `state`.
+    pub fn state_diff(mut self, state_diff: BTreeMap<H256, H256>) -> Self {
+        self.state = None;
+        self.state_diff = Some(state_diff);
+        self
+    }
+}
+
+/// Block header fields overridden during `eth_call` (fourth parameter, geth only).
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockOverrides {
+    /// Fake block number.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub number: Option<U64>,
+    /// Fake difficulty. Post-merge this is replaced by `prev_randao`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub difficulty: Option<U256>,
+    /// Fake block timestamp.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time: Option<U64>,
+    /// Fake block gas limit.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gas_limit: Option<U64>,
+    /// Fake block coinbase.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fee_recipient: Option<Address>,
+    /// Fake `PREVRANDAO` value.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prev_randao: Option<H256>,
+    /// Fake base fee per gas.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base_fee_per_gas: Option<U256>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serialize_state_override() {
+        let mut overrides = StateOverride::new();
+        overrides.insert(
+            Address::from_low_u64_be(0x123),
+            AccountOverride::default()
+                .balance(0x100.into())
+                .code(vec![0x60, 0x00].into())
+                .state_diff(
+                    [(H256::from_low_u64_be(1), H256::from_low_u64_be(2))]
+                        .into_iter()
+                        .collect(),
+                ),
+        );
+
+        let serialized = serde_json::to_value(&overrides).unwrap();
+        assert_eq!(
+            serialized,
+            serde_json::json!({
+                "0x0000000000000000000000000000000000000123": {
+                    "balance": "0x100",
+                    "code": "0x6000",
+                    "stateDiff": {
+                        "0x0000000000000000000000000000000000000000000000000000000000000001":
+                            "0x0000000000000000000000000000000000000000000000000000000000000002"
+                    }
+                }
+            })
+        );
+
+        let deserialized: StateOverride = serde_json::from_value(serialized).unwrap();
+        assert_eq!(deserialized, overrides);
+    }
+
+    #[test]
+    fn state_and_state_diff_are_exclusive() {
+        let slots: BTreeMap<H256, H256> = [(H256::zero(), H256::zero())].into_iter().collect();
+        let account = AccountOverride::default()
+            .state(slots.clone())
+            .state_diff(slots.clone());
+        assert_eq!(account.state, None);
+        assert_eq!(account.state_diff, Some(slots));
+    }
+
+    #[test]
+    fn serialize_block_overrides() {
+        let overrides = BlockOverrides {
+            number: Some(0x10.into()),
+            time: Some(0x20.into()),
+            base_fee_per_gas: Some(0x7.into()),
+            ..Default::default()
+        };
+
+        let serialized = serde_json::to_value(&overrides).unwrap();
+        assert_eq!(
+            serialized,
+            serde_json::json!({"number": "0x10", "time": "0x20", "baseFeePerGas": "0x7"})
+        );
+    }
+}
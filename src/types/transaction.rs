@@ -1,5 +1,6 @@
 use crate::prelude::*;
-use crate::types::{Address, Bytes, Index, Log, H2048, H256, U256, U64};
+use crate::types::{Address, BlobSidecar, Bytes, Index, Log, H2048, H256, U256, U64};
+use rlp::RlpStream;
 use serde::{Deserialize, Serialize};
 
 /// Description of a Transaction, pending or in the chain.
@@ -56,6 +57,15 @@ pub struct Transaction {
     /// miner bribe
     #[serde(rename = "maxPriorityFeePerGas", skip_serializing_if = "Option::is_none")]
     pub max_priority_fee_per_gas: Option<U256>,
+    /// Max fee per blob gas of blob transactions
+    #[serde(rename = "maxFeePerBlobGas", default, skip_serializing_if = "Option::is_none")]
+    pub max_fee_per_blob_gas: Option<U256>,
+    /// Versioned hashes of the blobs of blob transactions
+    #[serde(rename = "blobVersionedHashes", default, skip_serializing_if = "Option::is_none")]
+    pub blob_versioned_hashes: Option<Vec<H256>>,
+    /// Authorization list of set-code transactions
+    #[serde(rename = "authorizationList", default, skip_serializing_if = "Option::is_none")]
+    pub authorization_list: Option<AuthorizationList>,
 }
 
 /// "Receipt" of an executed transaction: details of its execution.
@@ -109,6 +119,12 @@ pub struct Receipt {
     /// Effective gas price
     #[serde(rename = "effectiveGasPrice")]
     pub effective_gas_price: Option<U256>,
+    /// Blob gas used by blob transactions
+    #[serde(rename = "blobGasUsed", default, skip_serializing_if = "Option::is_none")]
+    pub blob_gas_used: Option<U256>,
+    /// Blob gas price paid by blob transactions
+    #[serde(rename = "blobGasPrice", default, skip_serializing_if = "Option::is_none")]
+    pub blob_gas_price: Option<U256>,
 }
 
 /// Raw bytes of a signed, but not yet sent transaction
@@ -120,6 +136,17 @@ pub struct RawTransaction {
     pub tx: Transaction,
 }
 
+/// Signed transaction decoded from its raw bytes
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct DecodedTransaction {
+    /// Chain ID, None for legacy transactions signed without replay protection
+    pub chain_id: Option<u64>,
+    /// Transaction details, with the recovered sender, hash and raw bytes
+    pub tx: Transaction,
+    /// Sidecar of blob transactions in their network form
+    pub blob_sidecar: Option<BlobSidecar>,
+}
+
 /// Access list
 pub type AccessList = Vec<AccessListItem>;
 
@@ -133,6 +160,64 @@ pub struct AccessListItem {
     pub storage_keys: Vec<H256>,
 }
 
+/// Authorization list
+pub type AuthorizationList = Vec<SignedAuthorization>;
+
+/// Authorization of an account to delegate to contract code, from EIP-7702
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Authorization {
+    /// Chain ID the authorization is valid on, zero for any chain
+    pub chain_id: U256,
+    /// Address of the code to delegate to
+    pub address: Address,
+    /// Nonce of the authorizing account
+    pub nonce: U64,
+}
+
+impl Authorization {
+    /// Prefix of the signed authorization payload.
+    pub const MAGIC: u8 = 0x05;
+
+    /// Hash signed by the authorizing account, `keccak256(0x05 || rlp([chain_id, address, nonce]))`.
+    pub fn signature_hash(&self) -> H256 {
+        let mut stream = RlpStream::new_list(3);
+        stream.append(&self.chain_id);
+        stream.append(&self.address);
+        stream.append(&self.nonce);
+        crate::signing::keccak256(&[&[Self::MAGIC], stream.as_raw()].concat()).into()
+    }
+}
+
+/// Signed authorization item
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedAuthorization {
+    /// Chain ID the authorization is valid on, zero for any chain
+    pub chain_id: U256,
+    /// Address of the code to delegate to
+    pub address: Address,
+    /// Nonce of the authorizing account
+    pub nonce: U64,
+    /// ECDSA recovery id, 0 or 1
+    pub y_parity: U64,
+    /// ECDSA signature r, 32 bytes
+    pub r: U256,
+    /// ECDSA signature s, 32 bytes
+    pub s: U256,
+}
+
+impl SignedAuthorization {
+    /// The authorization that was signed.
+    pub fn authorization(&self) -> Authorization {
+        Authorization {
+            chain_id: self.chain_id,
+            address: self.address,
+            nonce: self.nonce,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::{RawTransaction, Receipt};